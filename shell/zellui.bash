@@ -0,0 +1,14 @@
+# zellui key binding for bash: press Alt-z to pick a session or layout and
+# run the chosen zellij command in this shell.
+#
+#   source /path/to/zellui/shell/zellui.bash
+
+__zellui_widget() {
+    local cmd
+    cmd="$(zellui --print)" || return
+    [ -n "$cmd" ] || return
+    history -s "$cmd"
+    eval "$cmd"
+}
+
+bind -x '"\ez": __zellui_widget'
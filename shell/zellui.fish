@@ -0,0 +1,16 @@
+# zellui key binding for fish: press Alt-z to pick a session or layout and
+# run the chosen zellij command in this shell.
+#
+#   source /path/to/zellui/shell/zellui.fish
+
+function __zellui_widget
+    set -l cmd (zellui --print)
+    if test -n "$cmd"
+        commandline --replace -- $cmd
+        commandline -f execute
+    else
+        commandline -f repaint
+    end
+end
+
+bind \ez __zellui_widget
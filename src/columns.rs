@@ -0,0 +1,254 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::kdl;
+
+/// zellui's config file, inside `helpers::get_zellui_config_dir`.
+pub const CONFIG_FILE: &str = "config.kdl";
+const BLOCK: &str = "session_columns";
+/// Widest a column can be set to, in the config or the picker.
+pub const MAX_WIDTH: u16 = 200;
+
+/// A piece of session metadata the Sessions table can show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnKind {
+    Name,
+    Status,
+    Age,
+    Created,
+    Tabs,
+    Clients,
+    /// Resident memory of the session's zellij server.
+    Memory,
+    Tags,
+    Notes,
+}
+
+impl ColumnKind {
+    pub const ALL: [ColumnKind; 9] = [
+        ColumnKind::Name,
+        ColumnKind::Status,
+        ColumnKind::Age,
+        ColumnKind::Created,
+        ColumnKind::Tabs,
+        ColumnKind::Clients,
+        ColumnKind::Memory,
+        ColumnKind::Tags,
+        ColumnKind::Notes,
+    ];
+
+    /// The name used in the config file.
+    pub fn name(self) -> &'static str {
+        match self {
+            ColumnKind::Name => "name",
+            ColumnKind::Status => "status",
+            ColumnKind::Age => "age",
+            ColumnKind::Created => "created",
+            ColumnKind::Tabs => "tabs",
+            ColumnKind::Clients => "clients",
+            ColumnKind::Memory => "memory",
+            ColumnKind::Tags => "tags",
+            ColumnKind::Notes => "notes",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+
+    pub fn header(self) -> &'static str {
+        match self {
+            ColumnKind::Name => "Name",
+            ColumnKind::Status => "Status",
+            ColumnKind::Age => "Age",
+            ColumnKind::Created => "Created",
+            ColumnKind::Tabs => "Tabs",
+            ColumnKind::Clients => "Clients",
+            ColumnKind::Memory => "Memory",
+            ColumnKind::Tags => "Tags",
+            ColumnKind::Notes => "Notes",
+        }
+    }
+
+    /// Width used when none is configured; `None` takes the remaining space.
+    pub fn default_width(self) -> Option<u16> {
+        match self {
+            ColumnKind::Name => None,
+            ColumnKind::Status => Some(7),
+            ColumnKind::Age => Some(14),
+            ColumnKind::Created => Some(16),
+            ColumnKind::Tabs => Some(4),
+            ColumnKind::Clients => Some(7),
+            ColumnKind::Memory => Some(7),
+            ColumnKind::Tags => Some(16),
+            ColumnKind::Notes => Some(24),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Column {
+    pub kind: ColumnKind,
+    /// Fixed width in cells; `None` uses `ColumnKind::default_width`.
+    pub width: Option<u16>,
+    pub shown: bool,
+}
+
+impl Column {
+    pub fn width(&self) -> Option<u16> {
+        self.width.or(self.kind.default_width())
+    }
+}
+
+/// Every column kind, shown ones first and in display order.
+pub fn default_columns() -> Vec<Column> {
+    let shown = [
+        ColumnKind::Name,
+        ColumnKind::Status,
+        ColumnKind::Age,
+        ColumnKind::Tabs,
+        ColumnKind::Clients,
+    ];
+    with_hidden(
+        shown
+            .into_iter()
+            .map(|kind| Column {
+                kind,
+                width: None,
+                shown: true,
+            })
+            .collect(),
+    )
+}
+
+/// Appends the kinds missing from `columns` as hidden columns.
+fn with_hidden(mut columns: Vec<Column>) -> Vec<Column> {
+    for kind in ColumnKind::ALL {
+        if !columns.iter().any(|c| c.kind == kind) {
+            columns.push(Column {
+                kind,
+                width: None,
+                shown: false,
+            });
+        }
+    }
+    columns
+}
+
+/// Reads the `session_columns` block of a zellui config:
+///
+/// ```kdl
+/// session_columns {
+///     name width=24
+///     status
+///     age
+/// }
+/// ```
+///
+/// Returns `None` when there is no such block.
+pub fn parse_columns(src: &str) -> Option<Vec<Column>> {
+    let mut found = false;
+    let mut inside = false;
+    let mut columns: Vec<Column> = Vec::new();
+    for node in kdl::parse_nodes(src) {
+        if node.depth == 0 {
+            inside = node.name == BLOCK;
+            found |= inside;
+            continue;
+        }
+        if !inside || node.depth != 1 {
+            continue;
+        }
+        let Some(kind) = ColumnKind::from_name(&node.name) else {
+            continue;
+        };
+        if columns.iter().any(|c| c.kind == kind) {
+            continue;
+        }
+        columns.push(Column {
+            kind,
+            width: node
+                .prop("width")
+                .and_then(|w| w.parse::<u16>().ok())
+                .map(|w| w.clamp(1, MAX_WIDTH)),
+            shown: true,
+        });
+    }
+    found.then(|| with_hidden(columns))
+}
+
+/// The `session_columns` block for the shown columns.
+pub fn columns_block(columns: &[Column]) -> String {
+    let mut block = format!("{} {{\n", BLOCK);
+    for column in columns.iter().filter(|c| c.shown) {
+        match column.width {
+            Some(width) => block.push_str(&format!("    {} width={}\n", column.kind.name(), width)),
+            None => block.push_str(&format!("    {}\n", column.kind.name())),
+        }
+    }
+    block.push_str("}\n");
+    block
+}
+
+/// Swaps the `session_columns` block in `src` for `block`, appending it when
+/// there is none, and leaves the rest of the file alone.
+pub fn replace_columns_block(src: &str, block: &str) -> String {
+    let lines: Vec<&str> = src.lines().collect();
+    let mut depth = 0i32;
+    let mut range = None;
+    for (i, line) in lines.iter().enumerate() {
+        let code = line.split("//").next().unwrap_or_default();
+        if depth == 0 && range.is_none() && code.trim_start().starts_with(BLOCK) {
+            range = Some((i, i));
+        }
+        depth += code.matches('{').count() as i32 - code.matches('}').count() as i32;
+        if let Some((start, _)) = range
+            && depth <= 0
+        {
+            range = Some((start, i));
+            break;
+        }
+    }
+
+    let mut out = String::new();
+    match range {
+        Some((start, end)) => {
+            for line in &lines[..start] {
+                out.push_str(line);
+                out.push('\n');
+            }
+            out.push_str(block);
+            for line in &lines[end + 1..] {
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+        None => {
+            out.push_str(src);
+            if !src.is_empty() && !src.ends_with('\n') {
+                out.push('\n');
+            }
+            out.push_str(block);
+        }
+    }
+    out
+}
+
+/// The columns configured in `config_dir`, or the defaults.
+pub fn load(config_dir: &Path) -> Vec<Column> {
+    fs::read_to_string(config_dir.join(CONFIG_FILE))
+        .ok()
+        .and_then(|src| parse_columns(&src))
+        .unwrap_or_else(default_columns)
+}
+
+/// Writes the shown columns into the config in `config_dir`, returning its
+/// path.
+pub fn save(config_dir: &Path, columns: &[Column]) -> io::Result<PathBuf> {
+    let path = config_dir.join(CONFIG_FILE);
+    let src = fs::read_to_string(&path).unwrap_or_default();
+    fs::create_dir_all(config_dir)?;
+    fs::write(&path, replace_columns_block(&src, &columns_block(columns)))?;
+    Ok(path)
+}
@@ -0,0 +1,65 @@
+use std::fs;
+use std::path::Path;
+
+use regex::Regex;
+
+use crate::columns::CONFIG_FILE;
+use crate::kdl;
+
+/// How the grouped session view clusters session names.
+#[derive(Debug, Clone)]
+pub enum GroupBy {
+    /// The part of the name before the first delimiter, e.g. `api` for
+    /// `api-main` with `-`.
+    Delimiter(String),
+    /// The first capture group of the regex (or the whole match without one).
+    Regex(Regex),
+}
+
+impl Default for GroupBy {
+    fn default() -> Self {
+        GroupBy::Delimiter("-".to_string())
+    }
+}
+
+impl GroupBy {
+    /// The group `name` belongs to; `None` leaves it ungrouped.
+    pub fn key(&self, name: &str) -> Option<String> {
+        let key = match self {
+            GroupBy::Delimiter(delimiter) => name.split_once(delimiter.as_str())?.0,
+            GroupBy::Regex(regex) => {
+                let captures = regex.captures(name)?;
+                captures.get(1).or_else(|| captures.get(0))?.as_str()
+            }
+        };
+        (!key.is_empty()).then(|| key.to_string())
+    }
+}
+
+/// Reads the `session_groups` node of a zellui config, either
+/// `session_groups delimiter="/"` or `session_groups regex=r"^(\w+)-"`.
+/// Returns `Ok(None)` when the node is missing.
+pub fn parse_group_by(src: &str) -> Result<Option<GroupBy>, String> {
+    let Some(node) = kdl::parse_nodes(src)
+        .into_iter()
+        .find(|n| n.depth == 0 && n.name == "session_groups")
+    else {
+        return Ok(None);
+    };
+    if let Some(pattern) = node.prop("regex") {
+        return Regex::new(pattern)
+            .map(|regex| Some(GroupBy::Regex(regex)))
+            .map_err(|e| format!("session_groups: {}", e));
+    }
+    match node.prop("delimiter").or(node.prop("prefix")) {
+        Some("") => Err("session_groups: empty delimiter".to_string()),
+        Some(delimiter) => Ok(Some(GroupBy::Delimiter(delimiter.to_string()))),
+        None => Err("session_groups needs delimiter= or regex=".to_string()),
+    }
+}
+
+/// The grouping configured in `config_dir`, or the default `-` delimiter.
+pub fn load(config_dir: &Path) -> Result<GroupBy, String> {
+    let src = fs::read_to_string(config_dir.join(CONFIG_FILE)).unwrap_or_default();
+    parse_group_by(&src).map(Option::unwrap_or_default)
+}
@@ -1,33 +1,281 @@
+use std::fmt;
 use std::fs;
+use std::io::{self, IsTerminal};
 use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::time::{Duration, SystemTime};
 
-pub fn parse_time(s: &str) -> i64 {
-    let mut total_seconds = 0;
-    let parts = s.split_whitespace();
-    for part in parts {
-        if let Some(val) = part.strip_suffix("day") {
-            if let Ok(n) = val.parse::<i64>() {
-                total_seconds += n * 24 * 3600;
-            }
-        } else if let Some(val) = part.strip_suffix("days") {
-            if let Ok(n) = val.parse::<i64>() {
-                total_seconds += n * 24 * 3600;
-            }
-        } else if let Some(val) = part.strip_suffix("h") {
-            if let Ok(n) = val.parse::<i64>() {
-                total_seconds += n * 3600;
-            }
-        } else if let Some(val) = part.strip_suffix("m") {
-            if let Ok(n) = val.parse::<i64>() {
-                total_seconds += n * 60;
-            }
-        } else if let Some(val) = part.strip_suffix("s") {
-            if let Ok(n) = val.parse::<i64>() {
-                total_seconds += n;
+use chrono::{DateTime, Local, TimeZone, Utc};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Parses the age zellij prints in `zellij ls` (humantime style, e.g.
+/// `"2days 4h 3m 12s"`, `"1year 2months"`, `"3weeks"`) into a duration.
+/// Sub-second parts are accepted and dropped. Fails on an empty string or
+/// any token it doesn't recognise, rather than guessing.
+pub fn parse_time(s: &str) -> Result<Duration, String> {
+    const MINUTE: u64 = 60;
+    const HOUR: u64 = 60 * MINUTE;
+    const DAY: u64 = 24 * HOUR;
+    // humantime's month and year: 30.44 and 365.25 days
+    const MONTH: u64 = 2_630_016;
+    const YEAR: u64 = 31_557_600;
+
+    if s.trim().is_empty() {
+        return Err("empty age".to_string());
+    }
+    let mut total: u64 = 0;
+    for part in s.split_whitespace() {
+        let split = part
+            .find(|c: char| !c.is_ascii_digit())
+            .filter(|&i| i > 0)
+            .ok_or_else(|| format!("bad age token: {}", part))?;
+        let (digits, unit) = part.split_at(split);
+        let n: u64 = digits
+            .parse()
+            .map_err(|_| format!("bad age token: {}", part))?;
+        let size = match unit {
+            "ns" | "nsec" | "us" | "usec" | "ms" | "msec" | "millis" => 0,
+            "s" | "sec" | "secs" | "second" | "seconds" => 1,
+            "m" | "min" | "mins" | "minute" | "minutes" => MINUTE,
+            "h" | "hr" | "hrs" | "hour" | "hours" => HOUR,
+            "d" | "day" | "days" => DAY,
+            "w" | "week" | "weeks" => 7 * DAY,
+            "M" | "month" | "months" => MONTH,
+            "y" | "year" | "years" => YEAR,
+            _ => return Err(format!("unknown unit in age: {}", part)),
+        };
+        total = n
+            .checked_mul(size)
+            .and_then(|secs| total.checked_add(secs))
+            .ok_or_else(|| format!("age out of range: {}", s))?;
+    }
+    Ok(Duration::from_secs(total))
+}
+
+/// Formats a number of seconds as a compact age using its two largest units,
+/// e.g. `273600` becomes `"3d 4h"`.
+pub fn format_age(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+    let units = [("d", 24 * 3600), ("h", 3600), ("m", 60), ("s", 1)];
+    let parts: Vec<String> = units
+        .iter()
+        .scan(seconds, |rest, &(suffix, size)| {
+            let n = *rest / size;
+            *rest %= size;
+            Some((n, suffix))
+        })
+        .skip_while(|&(n, _)| n == 0)
+        .take(2)
+        .filter(|&(n, _)| n > 0)
+        .map(|(n, suffix)| format!("{}{}", n, suffix))
+        .collect();
+    if parts.is_empty() {
+        "0s".to_string()
+    } else {
+        parts.join(" ")
+    }
+}
+
+/// Formats `time` as `YYYY-MM-DD HH:MM` in `zone`, with the offset in
+/// effect at that instant, so times across a DST change are each right.
+pub fn format_timestamp<Tz: TimeZone>(time: SystemTime, zone: &Tz) -> String
+where
+    Tz::Offset: fmt::Display,
+{
+    DateTime::<Utc>::from(time)
+        .with_timezone(zone)
+        .format("%Y-%m-%d %H:%M")
+        .to_string()
+}
+
+/// `format_timestamp` in the local time zone.
+pub fn format_local_timestamp(time: SystemTime) -> String {
+    format_timestamp(time, &Local)
+}
+
+/// Formats a size in KiB with one unit, e.g. `"12.3M"`.
+pub fn format_kb(kb: u64) -> String {
+    match kb {
+        0..1024 => format!("{}K", kb),
+        1024..1_048_576 => format!("{:.1}M", kb as f64 / 1024.0),
+        _ => format!("{:.1}G", kb as f64 / 1_048_576.0),
+    }
+}
+
+/// Cuts `s` down to `width` terminal cells, ending with `…` when anything was
+/// dropped. Wide characters (CJK, emoji) count as two cells.
+pub fn truncate_to_width(s: &str, width: usize) -> String {
+    if s.width() <= width {
+        return s.to_string();
+    }
+    let Some(room) = width.checked_sub(1) else {
+        return String::new();
+    };
+    let mut out = String::new();
+    let mut used = 0;
+    for c in s.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w > room {
+            break;
+        }
+        used += w;
+        out.push(c);
+    }
+    out.push('…');
+    out
+}
+
+/// Moves an optional selection one step through a list of `len` items, where
+/// `None` is an extra entry in front of the first item. Wraps in both directions.
+pub fn cycle_optional(selected: Option<usize>, len: usize, forward: bool) -> Option<usize> {
+    let positions = len + 1;
+    let current = selected.map_or(0, |i| i + 1);
+    let next = if forward {
+        (current + 1) % positions
+    } else {
+        (current + positions - 1) % positions
+    };
+    next.checked_sub(1)
+}
+
+/// Opens `path` in `$VISUAL`, then `$EDITOR`, then `vi`, and waits for it.
+/// The variables may carry arguments, e.g. `code --wait`.
+pub fn open_in_editor(path: &Path) -> io::Result<ExitStatus> {
+    let editor = ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string());
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or("vi");
+    let mut command = Command::new(program);
+    command.args(words).arg(path);
+    // In `--print` mode stdout is captured by the shell, so the editor
+    // draws on stderr, which is where the TUI lives too.
+    if !io::stdout().is_terminal() {
+        command.stdout(Stdio::from(io::stderr()));
+    }
+    command.status()
+}
+
+/// Quotes `word` for POSIX shells, leaving plain words untouched.
+pub fn shell_quote(word: &str) -> String {
+    let plain = !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=@%+,".contains(c));
+    if plain {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', "'\\''"))
+    }
+}
+
+/// The zellij invocation for `args` as a line a shell can `eval`.
+pub fn shell_command(args: &[String]) -> String {
+    std::iter::once("zellij".to_string())
+        .chain(args.iter().map(|arg| shell_quote(arg)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+pub fn base64(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
             }
         }
     }
-    total_seconds
+    out
+}
+
+/// The OSC 52 escape sequence asking the terminal to put `text` on the
+/// system clipboard; works over SSH and inside zellij.
+pub fn osc52(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64(text.as_bytes()))
+}
+
+/// Removes ANSI escape sequences (CSI, OSC and two-byte escapes) and stray
+/// control characters, leaving plain text.
+pub fn strip_ansi(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\u{1b}' => match chars.next() {
+                Some('[') => {
+                    // CSI: parameters, then a final byte in @..~
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                Some(']') => {
+                    // OSC: terminated by BEL or ST (ESC \)
+                    while let Some(c) = chars.next() {
+                        if c == '\u{7}' {
+                            break;
+                        }
+                        if c == '\u{1b}' && chars.peek() == Some(&'\\') {
+                            chars.next();
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            '\t' => out.push_str("    "),
+            c if c.is_control() && c != '\n' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// The last `n` lines of a screen dump with ANSI codes removed, ignoring the
+/// blank rows below the cursor.
+pub fn last_lines(screen: &str, n: usize) -> Vec<String> {
+    let lines: Vec<String> = screen.lines().map(strip_ansi).collect();
+    let end = lines
+        .iter()
+        .rposition(|line| !line.trim().is_empty())
+        .map_or(0, |i| i + 1);
+    lines[end.saturating_sub(n)..end].to_vec()
+}
+
+/// The zellij session this process runs in, if it was started from a pane.
+pub fn current_zellij_session() -> Option<String> {
+    std::env::var_os("ZELLIJ")?;
+    std::env::var("ZELLIJ_SESSION_NAME")
+        .ok()
+        .filter(|name| !name.is_empty())
+}
+
+/// The exit code to pass on for a child's status, following the shell's
+/// `128 + signal` convention for children killed by a signal.
+pub fn exit_code(status: ExitStatus) -> i32 {
+    if let Some(code) = status.code() {
+        return code;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+    1
 }
 
 pub fn get_zellij_config_dir() -> Option<PathBuf> {
@@ -39,13 +287,73 @@ pub fn get_zellij_config_dir() -> Option<PathBuf> {
     })
 }
 
+/// zellui's own config directory: `$XDG_CONFIG_HOME/zellui`, falling back to
+/// `~/.config/zellui`.
+pub fn get_zellui_config_dir() -> Option<PathBuf> {
+    let base = std::env::var("XDG_CONFIG_HOME")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var("HOME")
+                .ok()
+                .map(|h| PathBuf::from(h).join(".config"))
+        })?;
+    Some(base.join("zellui"))
+}
+
+/// zellij's cache directory, where it serializes sessions for resurrection:
+/// `$XDG_CACHE_HOME/zellij`, `~/.cache/zellij`, or the macOS equivalent.
+pub fn get_zellij_cache_dir() -> Option<PathBuf> {
+    if let Ok(dir) = std::env::var("XDG_CACHE_HOME")
+        && !dir.is_empty()
+    {
+        return Some(PathBuf::from(dir).join("zellij"));
+    }
+    let home = PathBuf::from(std::env::var("HOME").ok()?);
+    let macos = home
+        .join("Library")
+        .join("Caches")
+        .join("org.Zellij-Contributors.Zellij");
+    if macos.is_dir() {
+        Some(macos)
+    } else {
+        Some(home.join(".cache").join("zellij"))
+    }
+}
+
+pub fn get_zellij_layout_dir() -> Option<PathBuf> {
+    get_zellij_config_dir().map(|mut path| {
+        path.push("layouts");
+        path
+    })
+}
+
+/// zellui's own data directory: `$XDG_DATA_HOME/zellui`, falling back to
+/// `~/.local/share/zellui`.
+pub fn get_zellui_data_dir() -> Option<PathBuf> {
+    let base = std::env::var("XDG_DATA_HOME")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var("HOME").ok().map(|h| {
+                let mut path = PathBuf::from(h);
+                path.push(".local");
+                path.push("share");
+                path
+            })
+        })?;
+    Some(base.join("zellui"))
+}
+
 pub fn list_kdl_files(dir: &Path) -> Vec<String> {
     if let Ok(entries) = fs::read_dir(dir) {
         let mut files: Vec<String> = entries
             .filter_map(|entry| {
                 let entry = entry.ok()?;
                 let path = entry.path();
-                if path.is_file() && path.extension().map_or(false, |ext| ext == "kdl") {
+                if path.is_file() && path.extension().is_some_and(|ext| ext == "kdl") {
                     Some(path.file_name()?.to_string_lossy().into_owned())
                 } else {
                     None
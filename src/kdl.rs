@@ -0,0 +1,557 @@
+use crate::{ConfigMeta, LayoutMeta, PaneInfo, TabInfo};
+
+/// A single KDL node, flattened out of the document tree. `depth` is the
+/// number of enclosing `{ }` blocks, which is enough to recover the
+/// tab → pane nesting without building a full tree.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Node {
+    pub name: String,
+    pub depth: usize,
+    pub args: Vec<String>,
+    pub props: Vec<(String, String)>,
+}
+
+impl Node {
+    pub fn prop(&self, key: &str) -> Option<&str> {
+        self.props
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn arg(&self, index: usize) -> Option<&str> {
+        self.args.get(index).map(String::as_str)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    Str(String),
+    Eq,
+    Open,
+    Close,
+    Terminator,
+    SlashDash,
+    /// A string still open at the end of input.
+    Unterminated,
+}
+
+fn tokenize(src: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = src.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' | ';' => tokens.push(Token::Terminator),
+            '{' => tokens.push(Token::Open),
+            '}' => tokens.push(Token::Close),
+            '=' => tokens.push(Token::Eq),
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        tokens.push(Token::Terminator);
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = '\0';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            '/' if chars.peek() == Some(&'-') => {
+                chars.next();
+                tokens.push(Token::SlashDash);
+            }
+            '"' => {
+                let mut value = String::new();
+                let mut closed = false;
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => {
+                            closed = true;
+                            break;
+                        }
+                        '\\' => match chars.next() {
+                            Some('n') => value.push('\n'),
+                            Some('t') => value.push('\t'),
+                            Some(other) => value.push(other),
+                            None => break,
+                        },
+                        _ => value.push(c),
+                    }
+                }
+                tokens.push(if closed {
+                    Token::Str(value)
+                } else {
+                    Token::Unterminated
+                });
+            }
+            'r' if matches!(chars.peek(), Some('"') | Some('#')) => {
+                let mut hashes = 0;
+                while chars.peek() == Some(&'#') {
+                    chars.next();
+                    hashes += 1;
+                }
+                chars.next(); // opening quote
+                let mut value = String::new();
+                while let Some(c) = chars.next() {
+                    if c == '"' {
+                        let mut seen = 0;
+                        while seen < hashes && chars.peek() == Some(&'#') {
+                            chars.next();
+                            seen += 1;
+                        }
+                        if seen == hashes {
+                            break;
+                        }
+                        value.push('"');
+                        value.extend(std::iter::repeat_n('#', seen));
+                    } else {
+                        value.push(c);
+                    }
+                }
+                tokens.push(Token::Str(value));
+            }
+            '\\' => {
+                // Line continuation: swallow the following newline.
+                while let Some(&c) = chars.peek() {
+                    chars.next();
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            c if c.is_whitespace() => {}
+            _ => {
+                let mut word = String::from(c);
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || matches!(c, '{' | '}' | ';' | '=' | '"') {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+    tokens
+}
+
+/// Pushes a completed node, unless it was slash-dashed. Returns whether the
+/// node was dropped so its children can be skipped too.
+fn finish(node: Option<Node>, skip: &mut bool, nodes: &mut Vec<Node>) -> bool {
+    match node {
+        Some(_) if *skip => {
+            *skip = false;
+            true
+        }
+        Some(node) => {
+            nodes.push(node);
+            false
+        }
+        None => false,
+    }
+}
+
+/// Parses a KDL document into a flat, document-ordered list of nodes.
+///
+/// This is deliberately forgiving: it only understands enough of KDL to read
+/// zellij layouts and configs, and never fails — malformed input just yields
+/// fewer nodes.
+pub fn parse_nodes(src: &str) -> Vec<Node> {
+    let mut nodes = Vec::new();
+    let mut depth = 0usize;
+    let mut current: Option<Node> = None;
+    let mut skip_next = false;
+    // Depth at which a slash-dashed node's children are being skipped.
+    let mut skip_depth: Option<usize> = None;
+    let mut tokens = tokenize(src).into_iter().peekable();
+
+    while let Some(token) = tokens.next() {
+        let skipping = skip_depth.is_some_and(|d| depth > d);
+        match token {
+            Token::Terminator => {
+                if !skipping {
+                    finish(current.take(), &mut skip_next, &mut nodes);
+                }
+                current = None;
+            }
+            Token::Open => {
+                if !skipping {
+                    let dropped = finish(current.take(), &mut skip_next, &mut nodes);
+                    if dropped {
+                        skip_depth = Some(depth);
+                    }
+                }
+                current = None;
+                depth += 1;
+            }
+            Token::Close => {
+                if !skipping {
+                    finish(current.take(), &mut skip_next, &mut nodes);
+                }
+                current = None;
+                depth = depth.saturating_sub(1);
+                if skip_depth == Some(depth) {
+                    skip_depth = None;
+                }
+            }
+            _ if skipping => {}
+            Token::SlashDash => {
+                if current.is_none() {
+                    skip_next = true;
+                } else {
+                    // Slash-dashed argument or property: drop the next value.
+                    tokens.next();
+                    if tokens.peek() == Some(&Token::Eq) {
+                        tokens.next();
+                        tokens.next();
+                    }
+                }
+            }
+            Token::Eq | Token::Unterminated => {}
+            Token::Word(value) | Token::Str(value) => match current.as_mut() {
+                None => {
+                    current = Some(Node {
+                        name: value,
+                        depth,
+                        ..Node::default()
+                    });
+                }
+                Some(node) => {
+                    if tokens.peek() == Some(&Token::Eq) {
+                        tokens.next();
+                        if let Some(Token::Word(v) | Token::Str(v)) = tokens.next() {
+                            node.props.push((value, v));
+                        }
+                    } else {
+                        node.args.push(value);
+                    }
+                }
+            },
+        }
+    }
+    finish(current.take(), &mut skip_next, &mut nodes);
+    nodes
+}
+
+/// Cheap structural check run after a file is edited: catches the unbalanced
+/// braces and unterminated strings that a half-finished edit tends to leave.
+pub fn validate(src: &str) -> Result<(), String> {
+    let mut depth = 0i64;
+    for token in tokenize(src) {
+        match token {
+            Token::Open => depth += 1,
+            Token::Close => {
+                depth -= 1;
+                if depth < 0 {
+                    return Err("unexpected '}'".to_string());
+                }
+            }
+            Token::Unterminated => return Err("unterminated string".to_string()),
+            _ => {}
+        }
+    }
+    if depth > 0 {
+        Err(format!("{} unclosed '{{'", depth))
+    } else {
+        Ok(())
+    }
+}
+
+/// Removes what only makes sense for the session a layout was serialized
+/// from: the session's base `cwd`, `start_suspended` guards added for
+/// resurrection and `contents_file` scrollback snapshots.
+pub fn strip_session_bits(src: &str) -> String {
+    strip_serialized(src, true)
+}
+
+/// Like `strip_session_bits`, but keeps the base `cwd` that pane `cwd`s are
+/// relative to, for a session started in the same directories.
+pub fn strip_snapshot_bits(src: &str) -> String {
+    strip_serialized(src, false)
+}
+
+fn strip_serialized(src: &str, drop_base_cwd: bool) -> String {
+    let mut out = String::with_capacity(src.len());
+    let mut depth = 0usize;
+    for line in src.lines() {
+        let tokens = tokenize(line);
+        let name = match tokens.first() {
+            Some(Token::Word(name)) => Some(name.as_str()),
+            _ => None,
+        };
+        let drop = match name {
+            Some("start_suspended") => true,
+            // The layout-level cwd, directly inside `layout { }`
+            Some("cwd") => drop_base_cwd && depth == 1,
+            _ => false,
+        };
+        if !drop {
+            out.push_str(&strip_prop(line, "contents_file"));
+            out.push('\n');
+        }
+        for token in tokens {
+            match token {
+                Token::Open => depth += 1,
+                Token::Close => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+    }
+    out
+}
+
+/// Removes a `key="value"` property from a single line.
+fn strip_prop(line: &str, key: &str) -> String {
+    let pattern = format!(" {}=\"", key);
+    let Some(start) = line.find(&pattern) else {
+        return line.to_string();
+    };
+    let value_start = start + pattern.len();
+    let mut escaped = false;
+    let end = line[value_start..].char_indices().find_map(|(i, c)| {
+        let found = c == '"' && !escaped;
+        escaped = c == '\\' && !escaped;
+        found.then_some(value_start + i + 1)
+    });
+    match end {
+        Some(end) => format!("{}{}", &line[..start], &line[end..]),
+        None => line.to_string(),
+    }
+}
+
+/// Collects the `//` comment block at the top of a file, before the first node.
+pub fn leading_comment(src: &str) -> Option<String> {
+    let mut lines = Vec::new();
+    for line in src.lines() {
+        let line = line.trim();
+        if let Some(comment) = line.strip_prefix("//") {
+            let comment = comment.trim_start_matches('/').trim();
+            if !comment.is_empty() {
+                lines.push(comment.to_string());
+            }
+        } else if !line.is_empty() {
+            break;
+        }
+    }
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join(" "))
+    }
+}
+
+pub fn layout_meta(src: &str) -> LayoutMeta {
+    let nodes = parse_nodes(src);
+    let mut meta = LayoutMeta {
+        description: leading_comment(src),
+        ..LayoutMeta::default()
+    };
+
+    // Templates still name the commands and plugins a layout runs, but their
+    // tabs and panes aren't counted
+    let mut template_below: Option<usize> = None;
+    for (i, node) in nodes.iter().enumerate() {
+        if template_below.is_some_and(|depth| node.depth <= depth) {
+            template_below = None;
+        }
+        if template_below.is_none() && TEMPLATE_NODES.contains(&node.name.as_str()) {
+            template_below = Some(node.depth);
+        }
+        let counted = template_below.is_none();
+        match node.name.as_str() {
+            "tab" if counted => meta.tabs += 1,
+            "pane" => {
+                if counted && is_leaf_pane(&nodes[i + 1..], node.depth) {
+                    meta.panes += 1;
+                }
+                if let Some(command) = node.prop("command")
+                    && !meta.commands.iter().any(|c| c == command)
+                {
+                    meta.commands.push(command.to_string());
+                }
+                if let Some(plugin) = node.prop("plugin")
+                    && !meta.plugins.iter().any(|p| p == plugin)
+                {
+                    meta.plugins.push(plugin.to_string());
+                }
+            }
+            "plugin" => {
+                if let Some(location) = node.prop("location").or(node.arg(0))
+                    && !meta.plugins.iter().any(|p| p == location)
+                {
+                    meta.plugins.push(location.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    meta
+}
+
+/// Whether the pane at `depth`, followed by `rest`, has no panes of its own,
+/// i.e. isn't just a split container.
+fn is_leaf_pane(rest: &[Node], depth: usize) -> bool {
+    !rest
+        .iter()
+        .take_while(|n| n.depth > depth)
+        .any(|n| n.depth == depth + 1 && n.name == "pane")
+}
+
+pub fn config_meta(src: &str) -> ConfigMeta {
+    let mut meta = ConfigMeta::default();
+    for node in parse_nodes(src).into_iter().filter(|n| n.depth == 0) {
+        let value = node.arg(0).map(str::to_string);
+        match node.name.as_str() {
+            "default_layout" => meta.default_layout = value,
+            "default_mode" => meta.default_mode = value,
+            "theme" => meta.theme = value,
+            _ => {}
+        }
+    }
+    meta
+}
+
+/// Blocks that describe panes zellij *could* open rather than ones that exist.
+const TEMPLATE_NODES: [&str; 6] = [
+    "default_tab_template",
+    "new_tab_template",
+    "tab_template",
+    "pane_template",
+    "swap_tiled_layout",
+    "swap_floating_layout",
+];
+
+fn join_cwd(base: Option<&str>, cwd: Option<&str>) -> Option<String> {
+    match (base, cwd) {
+        (Some(base), Some(cwd)) if !cwd.starts_with('/') && !cwd.starts_with('~') => {
+            Some(format!("{}/{}", base.trim_end_matches('/'), cwd))
+        }
+        (_, Some(cwd)) => Some(cwd.to_string()),
+        (base, None) => base.map(str::to_string),
+    }
+}
+
+/// Is this pane just zellij's own tab or status bar?
+fn is_chrome(pane: &PaneInfo) -> bool {
+    pane.plugin
+        .as_deref()
+        .is_some_and(|p| p.starts_with("zellij:") && p.ends_with("-bar"))
+}
+
+/// Extracts the tabs of a layout — typically one dumped from a live session —
+/// with their leaf panes and resolved working directories.
+pub fn layout_tabs(src: &str) -> Vec<TabInfo> {
+    let mut tabs: Vec<TabInfo> = Vec::new();
+    // Panes of the current tab as (depth, pane, has child panes).
+    let mut panes: Vec<(usize, PaneInfo, bool)> = Vec::new();
+    // Indices into `panes` whose `{ }` block is still open.
+    let mut open: Vec<usize> = Vec::new();
+    let mut layout_cwd: Option<String> = None;
+    let mut tab: Option<(usize, Option<String>)> = None;
+    let mut skip_below: Option<usize> = None;
+
+    let flush = |tabs: &mut Vec<TabInfo>, panes: &mut Vec<(usize, PaneInfo, bool)>| {
+        let leaves = panes
+            .drain(..)
+            .filter(|(_, pane, split)| !split && !is_chrome(pane))
+            .map(|(_, pane, _)| pane);
+        if let Some(tab) = tabs.last_mut() {
+            tab.panes.extend(leaves);
+        }
+    };
+
+    for node in parse_nodes(src) {
+        if let Some(depth) = skip_below {
+            if node.depth > depth {
+                continue;
+            }
+            skip_below = None;
+        }
+        if TEMPLATE_NODES.contains(&node.name.as_str()) {
+            skip_below = Some(node.depth);
+            continue;
+        }
+        if let Some((depth, _)) = tab
+            && node.depth <= depth
+        {
+            flush(&mut tabs, &mut panes);
+            open.clear();
+            tab = None;
+        }
+        while open.last().is_some_and(|&i| panes[i].0 >= node.depth) {
+            open.pop();
+        }
+
+        match node.name.as_str() {
+            "cwd" if tab.is_none() => layout_cwd = node.arg(0).map(str::to_string),
+            "tab" => {
+                let cwd = join_cwd(layout_cwd.as_deref(), node.prop("cwd"));
+                tabs.push(TabInfo {
+                    name: node
+                        .prop("name")
+                        .map(str::to_string)
+                        .unwrap_or_else(|| format!("Tab #{}", tabs.len() + 1)),
+                    panes: Vec::new(),
+                    focused: node.prop("focus") == Some("true"),
+                });
+                tab = Some((node.depth, cwd));
+            }
+            "pane" => {
+                if tab.is_none() && tabs.is_empty() {
+                    // A layout without tabs is a single implicit tab.
+                    tabs.push(TabInfo {
+                        name: "Tab #1".to_string(),
+                        ..TabInfo::default()
+                    });
+                }
+                let base = tab
+                    .as_ref()
+                    .and_then(|(_, cwd)| cwd.as_deref())
+                    .or(layout_cwd.as_deref());
+                if let Some(&parent) = open.last()
+                    && panes[parent].0 + 1 == node.depth
+                {
+                    panes[parent].2 = true;
+                }
+                open.push(panes.len());
+                panes.push((
+                    node.depth,
+                    PaneInfo {
+                        command: node.prop("command").map(str::to_string),
+                        cwd: join_cwd(base, node.prop("cwd")),
+                        plugin: node.prop("plugin").map(str::to_string),
+                        focused: node.prop("focus") == Some("true"),
+                    },
+                    false,
+                ));
+            }
+            "args" | "plugin" => {
+                if let Some((depth, pane, _)) = panes.last_mut()
+                    && node.depth == *depth + 1
+                {
+                    if node.name == "plugin" {
+                        pane.plugin = node.prop("location").or(node.arg(0)).map(str::to_string);
+                    } else if let Some(command) = pane.command.as_mut() {
+                        for arg in &node.args {
+                            command.push(' ');
+                            command.push_str(arg);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    flush(&mut tabs, &mut panes);
+    tabs
+}
@@ -0,0 +1,196 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::kdl;
+
+/// Used when `zellij setup --dump-layout` is unavailable.
+const FALLBACK_TEMPLATE: &str = r#"layout {
+    pane size=1 borderless=true {
+        plugin location="zellij:tab-bar"
+    }
+    pane
+    pane size=2 borderless=true {
+        plugin location="zellij:status-bar"
+    }
+}
+"#;
+
+/// Turns user input into a layout file name: trims it, appends `.kdl` when
+/// missing and rejects anything that would escape the layouts directory.
+pub fn layout_file_name(input: &str) -> Result<String, String> {
+    let name = input.trim();
+    if name.is_empty() || name == ".kdl" {
+        return Err("Name cannot be empty".to_string());
+    }
+    if name.contains('/') || name.contains('\\') || name.starts_with('.') {
+        return Err(format!("Invalid layout name: {}", name));
+    }
+    if name.ends_with(".kdl") {
+        Ok(name.to_string())
+    } else {
+        Ok(format!("{}.kdl", name))
+    }
+}
+
+fn ensure_free(path: &Path) -> io::Result<()> {
+    if path.exists() {
+        Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", path.display()),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+fn sibling(src: &Path, name: &str) -> PathBuf {
+    src.with_file_name(name)
+}
+
+/// Moves a file, falling back to copy + remove across filesystems.
+fn move_file(from: &Path, to: &Path) -> io::Result<()> {
+    if fs::rename(from, to).is_err() {
+        fs::copy(from, to)?;
+        fs::remove_file(from)?;
+    }
+    Ok(())
+}
+
+pub fn duplicate(src: &Path, new_name: &str) -> io::Result<PathBuf> {
+    let dest = sibling(src, new_name);
+    ensure_free(&dest)?;
+    fs::copy(src, &dest)?;
+    Ok(dest)
+}
+
+pub fn rename(src: &Path, new_name: &str) -> io::Result<PathBuf> {
+    let dest = sibling(src, new_name);
+    ensure_free(&dest)?;
+    fs::rename(src, &dest)?;
+    Ok(dest)
+}
+
+/// Moves a layout into `trash_dir/<unix time>/`, so several files with the
+/// same name can be trashed and the most recent one restored first.
+pub fn trash(src: &Path, trash_dir: &Path) -> io::Result<PathBuf> {
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default();
+    let dir = trash_dir.join(stamp.to_string());
+    fs::create_dir_all(&dir)?;
+    let file_name = src
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "not a file"))?;
+    let dest = dir.join(file_name);
+    move_file(src, &dest)?;
+    Ok(dest)
+}
+
+/// Restores the most recently trashed layout into `layout_dir`. Returns
+/// `Ok(None)` when the trash is empty.
+pub fn restore_latest(trash_dir: &Path, layout_dir: &Path) -> io::Result<Option<PathBuf>> {
+    let Ok(entries) = fs::read_dir(trash_dir) else {
+        return Ok(None);
+    };
+    let mut batches: Vec<(u128, PathBuf)> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let stamp = path.file_name()?.to_str()?.parse::<u128>().ok()?;
+            Some((stamp, path))
+        })
+        .collect();
+    batches.sort();
+
+    while let Some((_, dir)) = batches.pop() {
+        let file = fs::read_dir(&dir)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .find(|path| path.is_file());
+        let Some(file) = file else {
+            let _ = fs::remove_dir(&dir);
+            continue;
+        };
+        let dest = layout_dir.join(file.file_name().unwrap_or_default());
+        ensure_free(&dest)?;
+        fs::create_dir_all(layout_dir)?;
+        move_file(&file, &dest)?;
+        let _ = fs::remove_dir(&dir);
+        return Ok(Some(dest));
+    }
+    Ok(None)
+}
+
+/// Asks zellij for the source of one of its built-in layouts.
+pub fn builtin_template(name: &str) -> String {
+    Command::new("zellij")
+        .args(["setup", "--dump-layout", name])
+        .output()
+        .ok()
+        .filter(|output| output.status.success() && !output.stdout.is_empty())
+        .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+        .unwrap_or_else(|| FALLBACK_TEMPLATE.to_string())
+}
+
+/// Writes a new layout file, refusing to replace an existing one.
+pub fn write_new(layout_dir: &Path, new_name: &str, contents: &str) -> io::Result<PathBuf> {
+    let dest = layout_dir.join(new_name);
+    ensure_free(&dest)?;
+    fs::create_dir_all(layout_dir)?;
+    fs::write(&dest, contents)?;
+    Ok(dest)
+}
+
+pub fn create_from_template(
+    layout_dir: &Path,
+    new_name: &str,
+    template_name: &str,
+    template: &str,
+) -> io::Result<PathBuf> {
+    let contents = format!(
+        "// Based on zellij's built-in \"{}\" layout\n{}",
+        template_name, template
+    );
+    write_new(layout_dir, new_name, &contents)
+}
+
+/// Turns a session's serialized layout into a reusable layout file.
+pub fn save_session_layout(
+    layout_dir: &Path,
+    new_name: &str,
+    session_name: &str,
+    layout: &str,
+) -> io::Result<PathBuf> {
+    let contents = format!(
+        "// Saved from session \"{}\"\n{}",
+        session_name,
+        kdl::strip_session_bits(layout)
+    );
+    write_new(layout_dir, new_name, &contents)
+}
+
+/// How long a clone's layout is kept; zellij reads it as the session starts.
+const CLONE_LAYOUT_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Writes the layout a cloned session starts from into `clone_dir`, in the
+/// original session's directories but without its scrollback and
+/// resurrection guards, and clears out clone layouts past `CLONE_LAYOUT_TTL`.
+pub fn write_clone_layout(clone_dir: &Path, new_name: &str, layout: &str) -> io::Result<PathBuf> {
+    fs::create_dir_all(clone_dir)?;
+    for entry in fs::read_dir(clone_dir)?.flatten() {
+        let stale = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age > CLONE_LAYOUT_TTL);
+        if stale {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+    let dest = clone_dir.join(format!("{}.kdl", new_name.replace(['/', '\\'], "_")));
+    fs::write(&dest, kdl::strip_snapshot_bits(layout))?;
+    Ok(dest)
+}
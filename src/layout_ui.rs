@@ -1,38 +1,158 @@
+use std::time::SystemTime;
+
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Layout, Rect},
-    style::Stylize,
-    widgets::{Block, List, ListItem, Widget},
+    style::{Style, Stylize},
+    text::{Line, Text},
+    widgets::{Block, List, ListItem, Paragraph, Widget, Wrap},
 };
 
-use crate::{App, InputMode, ListType};
+use crate::helpers::format_age;
+use crate::session_ui::pin_marker;
+use crate::{App, InputMode, KdlFile, ListType};
+
+/// One-line summary shown under each layout name.
+fn layout_summary(file: &KdlFile) -> String {
+    if file.builtin {
+        return file.meta.description.clone().unwrap_or_default();
+    }
+    let counts = format!("{} tabs, {} panes", file.meta.tabs, file.meta.panes);
+    match file.meta.description {
+        Some(ref description) => format!("{} · {}", counts, description),
+        None => counts,
+    }
+}
+
+fn selected_style(focused: bool) -> Style {
+    let style = Style::default().white().bold();
+    if focused {
+        style.on_blue()
+    } else {
+        style.on_black()
+    }
+}
+
+/// The "(none / zellij default)" entry at the top of each list.
+fn none_item(selected: bool, focused: bool) -> ListItem<'static> {
+    let item = ListItem::new("0. (none / zellij default)".italic());
+    if selected {
+        item.style(selected_style(focused))
+    } else {
+        item
+    }
+}
+
+fn render_file_details(file: Option<&KdlFile>, list: ListType, area: Rect, buf: &mut Buffer) {
+    let block = Block::bordered().title(" Details ".bold());
+    let Some(file) = file else {
+        Paragraph::new("No file selected: zellij will use its own default")
+            .block(block)
+            .render(area, buf);
+        return;
+    };
+
+    let meta = &file.meta;
+    let or_none = |items: &[String]| {
+        if items.is_empty() {
+            "-".to_string()
+        } else {
+            items.join(", ")
+        }
+    };
+    let modified = meta
+        .modified
+        .and_then(|m| SystemTime::now().duration_since(m).ok())
+        .map(|d| format!("{} ago", format_age(d.as_secs() as i64)))
+        .unwrap_or_else(|| "-".to_string());
+
+    let or_dash = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".to_string());
+
+    let mut lines = vec![Line::from(vec![
+        "Description: ".bold(),
+        or_dash(&meta.description).into(),
+    ])];
+    if list == ListType::Config {
+        let config = &file.config;
+        lines.push(Line::from(vec![
+            "Default layout: ".bold(),
+            or_dash(&config.default_layout).into(),
+            "  Default mode: ".bold(),
+            or_dash(&config.default_mode).into(),
+        ]));
+        lines.push(Line::from(vec![
+            "Theme: ".bold(),
+            or_dash(&config.theme).into(),
+            "  Modified: ".bold(),
+            modified.into(),
+        ]));
+    } else {
+        lines.push(Line::from(vec![
+            "Tabs: ".bold(),
+            meta.tabs.to_string().into(),
+            "  Panes: ".bold(),
+            meta.panes.to_string().into(),
+            "  Modified: ".bold(),
+            modified.into(),
+        ]));
+        lines.push(Line::from(vec![
+            "Commands: ".bold(),
+            or_none(&meta.commands).into(),
+        ]));
+        lines.push(Line::from(vec![
+            "Plugins: ".bold(),
+            or_none(&meta.plugins).into(),
+        ]));
+    }
+    lines.push(Line::from(vec![
+        "Path: ".bold(),
+        if file.builtin {
+            "(built into zellij)".dark_gray()
+        } else {
+            file.path.to_string_lossy().to_string().dark_gray()
+        },
+    ]));
+
+    Paragraph::new(Text::from(lines))
+        .wrap(Wrap { trim: true })
+        .block(block)
+        .render(area, buf);
+}
 
 pub fn render_layout_list(app: &App, area: Rect, buf: &mut Buffer) {
+    let outer_chunks = Layout::default()
+        .direction(ratatui::layout::Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(7)])
+        .split(area);
+
     let list_chunks = Layout::default()
         .direction(ratatui::layout::Direction::Horizontal)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-        .split(area);
+        .split(outer_chunks[0]);
 
     // Layouts List
-    let layout_items: Vec<ListItem> = app
-        .layouts
-        .iter()
-        .enumerate()
-        .map(|(i, f)| {
-            let content = format!("{}. {}", i + 1, f.name);
-            if i == app.selected_layout {
-                let mut style = ratatui::style::Style::default().white().bold();
-                if app.focused_list == ListType::Layout {
-                    style = style.on_blue();
-                } else {
-                    style = style.on_black();
-                }
-                ListItem::new(content).style(style)
-            } else {
-                ListItem::new(content)
-            }
-        })
-        .collect();
+    let layout_focused = app.focused_list == ListType::Layout;
+    let mut layout_items = vec![none_item(app.selected_layout.is_none(), layout_focused)];
+    layout_items.extend(app.layouts.iter().enumerate().map(|(i, f)| {
+        let pin = pin_marker(app.pins.layouts.contains(&f.layout_arg()));
+        let title = if f.builtin {
+            Line::from(vec![
+                format!("{}. {}{} ", i + 1, pin, f.name).italic(),
+                "[built-in]".cyan(),
+            ])
+        } else {
+            Line::from(format!("{}. {}{}", i + 1, pin, f.name))
+        };
+        let content = Text::from(vec![
+            title,
+            Line::from(format!("   {}", layout_summary(f)).dark_gray()),
+        ]);
+        if app.selected_layout == Some(i) {
+            ListItem::new(content).style(selected_style(layout_focused))
+        } else {
+            ListItem::new(content)
+        }
+    }));
 
     let layout_title =
         if app.focused_list == ListType::Layout && app.input_mode == InputMode::GoToIndex {
@@ -55,25 +175,16 @@ pub fn render_layout_list(app: &App, area: Rect, buf: &mut Buffer) {
     Widget::render(layout_list, list_chunks[0], buf);
 
     // Configs List
-    let config_items: Vec<ListItem> = app
-        .configs
-        .iter()
-        .enumerate()
-        .map(|(i, f)| {
-            let content = format!("{}. {}", i + 1, f.name);
-            if i == app.selected_config {
-                let mut style = ratatui::style::Style::default().white().bold();
-                if app.focused_list == ListType::Config {
-                    style = style.on_blue();
-                } else {
-                    style = style.on_black();
-                }
-                ListItem::new(content).style(style)
-            } else {
-                ListItem::new(content)
-            }
-        })
-        .collect();
+    let config_focused = app.focused_list == ListType::Config;
+    let mut config_items = vec![none_item(app.selected_config.is_none(), config_focused)];
+    config_items.extend(app.configs.iter().enumerate().map(|(i, f)| {
+        let content = format!("{}. {}", i + 1, f.name);
+        if app.selected_config == Some(i) {
+            ListItem::new(content).style(selected_style(config_focused))
+        } else {
+            ListItem::new(content)
+        }
+    }));
 
     let config_title =
         if app.focused_list == ListType::Config && app.input_mode == InputMode::GoToIndex {
@@ -94,4 +205,10 @@ pub fn render_layout_list(app: &App, area: Rect, buf: &mut Buffer) {
         .highlight_symbol(">> ");
 
     Widget::render(config_list, list_chunks[1], buf);
+
+    let details = match app.focused_list {
+        ListType::Config => app.selected_config_file(),
+        _ => app.selected_layout_file(),
+    };
+    render_file_details(details, app.focused_list, outer_chunks[1], buf);
 }
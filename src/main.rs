@@ -1,31 +1,160 @@
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::fs;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::{Duration, Instant, SystemTime};
 
 // helpers from crate
-use crate::helpers::{get_zellij_config_dir, list_kdl_files, parse_time};
-use color_eyre::eyre::Result;
+use crate::columns::Column;
+use crate::groups::GroupBy;
+use crate::helpers::{
+    current_zellij_session, cycle_optional, exit_code, get_zellij_config_dir,
+    get_zellij_layout_dir, get_zellui_config_dir, get_zellui_data_dir, last_lines, list_kdl_files,
+    open_in_editor, osc52, parse_time, shell_command,
+};
+use crate::state::{Pins, SessionMeta};
+use color_eyre::eyre::{Result, bail};
 
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
-use ratatui::DefaultTerminal;
+use crossterm::execute;
+use crossterm::terminal::{
+    EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+};
+use ratatui::Terminal;
+use ratatui::backend::{Backend, CrosstermBackend};
 
+pub mod columns;
+pub mod groups;
 pub mod helpers;
+pub mod kdl;
+pub mod layout_files;
 pub mod layout_ui;
 pub mod session_ui;
+pub mod state;
 pub mod ui;
+pub mod zellij;
 
 #[cfg(test)]
 mod tests;
 
+const USAGE: &str = "Usage: zellui [OPTIONS]
+
+Options:
+  -l, --loop           Return to zellui whenever zellij exits or detaches, until you quit
+  -p, --print          Print the chosen zellij command instead of running it
+      --print-session  Print only the chosen session's name
+  -h, --help           Print this help";
+
+/// What `--print` writes to stdout in place of running zellij.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PrintMode {
+    /// The shell-quoted zellij command line.
+    Command,
+    /// Just the session name.
+    Session,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Cli {
+    /// Launcher loop: come back to the TUI after zellij exits.
+    pub launcher: bool,
+    pub print: Option<PrintMode>,
+}
+
+impl Cli {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Option<Self>> {
+        let mut cli = Cli::default();
+        for arg in args {
+            match arg.as_str() {
+                "-l" | "--loop" => cli.launcher = true,
+                "-p" | "--print" => cli.print = Some(PrintMode::Command),
+                "--print-session" => cli.print = Some(PrintMode::Session),
+                "-h" | "--help" => return Ok(None),
+                other => bail!("unknown argument: {}\n\n{}", other, USAGE),
+            }
+        }
+        if cli.launcher && cli.print.is_some() {
+            bail!("--loop cannot be combined with --print\n\n{}", USAGE);
+        }
+        Ok(Some(cli))
+    }
+}
+
 fn main() -> Result<()> {
     color_eyre::install()?;
 
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let [flag, session, tab, clients] = args.as_slice()
+        && flag == zellij::FOCUS_TAB_FLAG
+    {
+        zellij::focus_tab_when_attached(session, tab, clients.parse().unwrap_or(0))?;
+        return Ok(());
+    }
+
+    let Some(cli) = Cli::parse(args)? else {
+        println!("{}", USAGE);
+        return Ok(());
+    };
+
     let mut app = App::default();
-    let result = ratatui::run(|terminal| app.run(terminal))?;
-    if let Some(args) = result {
-        Command::new("zellij").args(args).status()?;
+    if let Some(mode) = cli.print {
+        // The shell may never run the printed command, so nothing is recorded
+        app.print_only = true;
+        let Some(args) = run_on_stderr(&mut app)? else {
+            return Ok(());
+        };
+        let line = match mode {
+            PrintMode::Command => Some(shell_command(&args)),
+            PrintMode::Session => App::session_from_args(&args),
+        };
+        if let Some(line) = line {
+            println!("{}", line);
+        }
+        return Ok(());
     }
-    Ok(())
+    loop {
+        let result = ratatui::run(|terminal| app.run(terminal))?;
+        let Some(args) = result else {
+            return Ok(());
+        };
+        app.record_attach(&args);
+        app.start_tab_focus();
+        if !cli.launcher {
+            let status = zellij::hand_over(&args)?;
+            if !status.success() {
+                std::process::exit(exit_code(status));
+            }
+            return Ok(());
+        }
+        let status = Command::new("zellij").args(&args).status();
+        app.resume(&args, status);
+    }
+}
+
+/// Runs the TUI on stderr, leaving stdout free for `--print` output that
+/// the calling shell captures.
+fn run_on_stderr(app: &mut App) -> Result<Option<Vec<String>>> {
+    // Like `ratatui::init`, put the terminal back before a panic is reported
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        restore_stderr();
+        hook(info);
+    }));
+    enable_raw_mode()?;
+    execute!(io::stderr(), EnterAlternateScreen)?;
+    let result = Terminal::new(CrosstermBackend::new(io::stderr())).and_then(|mut terminal| {
+        terminal.clear()?;
+        app.run(&mut terminal)
+    });
+    restore_stderr();
+    Ok(result?)
+}
+
+fn restore_stderr() {
+    let _ = execute!(io::stderr(), LeaveAlternateScreen);
+    let _ = disable_raw_mode();
 }
 
 #[derive(Debug, Default, Clone, Copy)]
@@ -38,14 +167,135 @@ pub struct Action {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Session {
     pub name: String,
-    pub time: i64,
+    /// When the session was created, worked out from the age `zellij ls`
+    /// reported at fetch time; `None` when that age couldn't be parsed.
+    pub created: Option<SystemTime>,
     pub exited: bool,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+impl Session {
+    /// Whole seconds since the session was created, if known.
+    pub fn age_secs(&self, now: SystemTime) -> Option<i64> {
+        let created = self.created?;
+        Some(
+            now.duration_since(created)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PaneInfo {
+    /// Command with its arguments; `None` for a plain shell.
+    pub command: Option<String>,
+    pub cwd: Option<String>,
+    pub plugin: Option<String>,
+    pub focused: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TabInfo {
+    pub name: String,
+    pub panes: Vec<PaneInfo>,
+    pub focused: bool,
+}
+
+/// The last lines on screen in a live session's focused pane.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScreenPreview {
+    pub session: String,
+    pub lines: Vec<String>,
+    pub error: Option<String>,
+}
+
+/// How long the selection has to rest on a session before its screen is
+/// dumped, so scrolling through the list doesn't spawn a zellij per row.
+pub const PREVIEW_DEBOUNCE: Duration = Duration::from_millis(300);
+/// How often the screen is redrawn while idle, so ages keep ticking.
+pub const TICK: Duration = Duration::from_secs(1);
+/// How often to check for session details while some are being fetched.
+pub const DETAIL_POLL: Duration = Duration::from_millis(50);
+/// Lines of the dump kept for the preview pane.
+pub const PREVIEW_LINES: usize = 200;
+
+/// What zellij can tell us about a session beyond `zellij ls`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionDetail {
+    pub tabs: Vec<TabInfo>,
+    /// Clients connected to a live session, when zellij reports them.
+    pub clients: Option<usize>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LayoutMeta {
+    pub description: Option<String>,
+    pub tabs: usize,
+    pub panes: usize,
+    pub commands: Vec<String>,
+    pub plugins: Vec<String>,
+    pub modified: Option<SystemTime>,
+}
+
+/// Session defaults read from a config file's top-level options.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigMeta {
+    pub default_layout: Option<String>,
+    pub default_mode: Option<String>,
+    pub theme: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct KdlFile {
     pub name: String,
     pub path: PathBuf,
+    pub meta: LayoutMeta,
+    pub config: ConfigMeta,
+    pub builtin: bool,
+}
+
+impl KdlFile {
+    pub fn from_path(name: String, path: PathBuf) -> Self {
+        let src = fs::read_to_string(&path).unwrap_or_default();
+        let mut meta = kdl::layout_meta(&src);
+        meta.modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+        Self {
+            name,
+            path,
+            meta,
+            config: kdl::config_meta(&src),
+            builtin: false,
+        }
+    }
+
+    pub fn builtin(name: &str, description: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            path: PathBuf::new(),
+            meta: LayoutMeta {
+                description: Some(description.to_string()),
+                ..LayoutMeta::default()
+            },
+            config: ConfigMeta::default(),
+            builtin: true,
+        }
+    }
+
+    /// The name zellij resolves this layout by, i.e. without `.kdl`.
+    pub fn layout_name(&self) -> &str {
+        self.name.strip_suffix(".kdl").unwrap_or(&self.name)
+    }
+
+    /// The value passed to zellij's `--layout`: built-ins are referenced by
+    /// bare name, files on disk by path.
+    pub fn layout_arg(&self) -> String {
+        if self.builtin {
+            self.name.clone()
+        } else {
+            self.path.to_string_lossy().to_string()
+        }
+    }
 }
 
 impl PartialEq<char> for Action {
@@ -66,7 +316,7 @@ impl PartialEq for Action {
     }
 }
 
-pub const ACTIONS: [Action; 4] = [
+pub const ACTIONS: [Action; 5] = [
     Action {
         code: 0,
         shortcut: 'a',
@@ -87,14 +337,116 @@ pub const ACTIONS: [Action; 4] = [
         shortcut: 'n',
         title: "New Session",
     },
+    Action {
+        code: 4,
+        shortcut: 'l',
+        title: "Sessions",
+    },
+];
+
+/// The config zellij reads when it isn't given `--config`.
+pub const ZELLIJ_CONFIG: &str = "config.kdl";
+
+/// Layouts compiled into zellij itself, as `(name, description)`.
+pub const BUILTIN_LAYOUTS: [(&str, &str); 4] = [
+    ("default", "Tab bar and status bar"),
+    ("compact", "Single compact status bar"),
+    ("strider", "File explorer pane beside the terminal"),
+    ("welcome", "Zellij welcome screen"),
 ];
 
+/// What the text typed into `App::input_buffer` is for.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Prompt {
+    DuplicateLayout,
+    RenameLayout,
+    /// New layout from the named built-in template.
+    NewLayout(&'static str),
+    /// Save the selected session's layout into the layouts directory.
+    SaveSessionLayout,
+    /// Name for a new session started from the selected session's layout.
+    CloneSession,
+    /// Tags of the selected session.
+    Tags,
+    /// Note on the selected session.
+    Note,
+    /// Tag the session list is filtered by; empty clears it.
+    TagFilter,
+}
+
+/// Operations waiting for a y/n answer.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Confirm {
+    TrashLayout,
+    RestoreLayout,
+    /// Kill the live sessions of the highlighted group.
+    KillGroup,
+    /// Delete the exited sessions of the highlighted group.
+    DeleteGroup,
+}
+
+/// A row of the session list. In the grouped view, sessions sharing a group
+/// key are listed under a header row.
+#[derive(Debug, PartialEq, Eq)]
+pub enum SessionRow<'a> {
+    Group {
+        name: String,
+        sessions: Vec<&'a Session>,
+        collapsed: bool,
+    },
+    Session(&'a Session),
+}
+
 #[derive(Debug, Default, PartialEq, Eq)]
 pub enum InputMode {
     #[default]
     Normal,
     GoToIndex,
     Status(String),
+    Input(Prompt),
+    Confirm(Confirm),
+    /// Picking the Sessions table's columns.
+    Columns,
+}
+
+/// Order of the session list, cycled with `O` and saved between runs.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum SortMode {
+    /// Newest first.
+    #[default]
+    Age,
+    Name,
+    /// Current session, then live ones, then exited ones.
+    Status,
+    /// Most recently attached to from zellui first.
+    Attached,
+}
+
+impl SortMode {
+    const ALL: [SortMode; 4] = [
+        SortMode::Age,
+        SortMode::Name,
+        SortMode::Status,
+        SortMode::Attached,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SortMode::Age => "age",
+            SortMode::Name => "name",
+            SortMode::Status => "status",
+            SortMode::Attached => "attached",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.name() == name)
+    }
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|&mode| mode == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
 }
 
 #[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
@@ -103,61 +455,326 @@ pub enum ListType {
     Layout,
     Config,
     Session,
+    /// Tabs of the selected live session, drilled into from the session list.
+    Tab,
 }
 
 #[derive(Debug)]
 pub struct App {
     pub action: Action,
     pub sessions: Vec<Session>,
+    pub sort_mode: SortMode,
+    /// When each session was last attached to from zellui, for `SortMode::Attached`.
+    pub last_attached: HashMap<String, SystemTime>,
+    pub pins: Pins,
+    /// Tags and notes per session name, saved in the data dir.
+    pub session_meta: HashMap<String, SessionMeta>,
+    /// Only sessions with all of these tags are listed.
+    pub tag_filter: Vec<String>,
+    /// The session zellui is running inside of, when launched from a zellij
+    /// pane; `None` in tests, so they never start real background sessions.
+    pub current_session: Option<String>,
+    /// Lazily fetched per session name; cleared on refresh.
+    pub session_details: HashMap<String, SessionDetail>,
+    pub detail_loader: zellij::DetailLoader,
+    /// Resident memory of each session's server, for the Memory column;
+    /// `None` until loaded, cleared on refresh.
+    pub server_memory: Option<HashMap<String, u64>>,
+    pub preview: Option<ScreenPreview>,
+    /// The session a preview is pending for and when to take it; restarted
+    /// whenever the highlighted session changes.
+    pub preview_due: Option<(String, Instant)>,
     pub layouts: Vec<KdlFile>,
     pub configs: Vec<KdlFile>,
     pub selected_session: usize,
-    pub selected_layout: usize,
-    pub selected_config: usize,
+    pub selected_tab: usize,
+    /// `None` selects the "(none / zellij default)" entry.
+    pub selected_layout: Option<usize>,
+    /// `None` selects the "(none / zellij default)" entry.
+    pub selected_config: Option<usize>,
     pub focused_list: ListType,
+    /// Show local creation timestamps instead of ages in the session list.
+    pub show_timestamps: bool,
+    /// Cluster the session list into collapsible groups.
+    pub grouped: bool,
+    pub group_by: GroupBy,
+    pub collapsed_groups: HashSet<String>,
+    /// Columns of the Sessions table, shown ones first.
+    pub columns: Vec<Column>,
+    pub column_cursor: usize,
     pub input_mode: InputMode,
     pub goto_buffer: String,
+    pub input_buffer: String,
     pub status_message: String,
+    /// File to open in `$EDITOR` once the current event has been handled,
+    /// since suspending the TUI needs the terminal.
+    pub pending_edit: Option<PathBuf>,
+    /// Text to send to the terminal clipboard on the next loop iteration.
+    pub pending_yank: Option<String>,
+    /// Session, tab and current client count for a tab picked to attach to;
+    /// focused by a helper once zellui has started the attach.
+    pub pending_tab_focus: Option<(String, String, usize)>,
+    /// Chosen commands are printed for the shell instead of run (`--print`),
+    /// so zellui must not start anything itself.
+    pub print_only: bool,
+    /// Where zellui keeps its state and config; `None` in tests, so they
+    /// neither read nor overwrite the user's files.
+    pub data_dir: Option<PathBuf>,
+    pub config_dir: Option<PathBuf>,
     pub exit: bool,
 }
 
 impl Default for App {
     fn default() -> Self {
+        let (data_dir, config_dir, current_session) = if cfg!(test) {
+            (None, None, None)
+        } else {
+            (
+                get_zellui_data_dir(),
+                get_zellui_config_dir(),
+                current_zellij_session(),
+            )
+        };
         let mut app = Self {
             action: ACTIONS[0],
             sessions: Vec::new(),
+            sort_mode: data_dir
+                .as_deref()
+                .map(state::load_sort_mode)
+                .unwrap_or_default(),
+            last_attached: data_dir
+                .as_deref()
+                .map(state::load_attach_history)
+                .unwrap_or_default(),
+            pins: data_dir
+                .as_deref()
+                .map(state::load_pins)
+                .unwrap_or_default(),
+            session_meta: data_dir
+                .as_deref()
+                .map(state::load_session_meta)
+                .unwrap_or_default(),
+            tag_filter: Vec::new(),
+            current_session,
+            session_details: HashMap::new(),
+            detail_loader: zellij::DetailLoader::default(),
+            server_memory: None,
+            preview: None,
+            preview_due: None,
             layouts: Vec::new(),
             configs: Vec::new(),
             selected_session: 0,
-            selected_layout: 0,
-            selected_config: 0,
+            selected_tab: 0,
+            selected_layout: None,
+            selected_config: None,
             focused_list: ListType::Layout,
+            show_timestamps: false,
+            grouped: false,
+            group_by: GroupBy::default(),
+            collapsed_groups: HashSet::new(),
+            columns: config_dir
+                .as_deref()
+                .map(columns::load)
+                .unwrap_or_else(columns::default_columns),
+            column_cursor: 0,
             input_mode: InputMode::Normal,
             goto_buffer: String::new(),
+            input_buffer: String::new(),
             status_message: String::new(),
+            pending_edit: None,
+            pending_yank: None,
+            pending_tab_focus: None,
+            print_only: false,
+            data_dir,
+            config_dir,
             exit: false,
         };
+        if let Some(dir) = app.config_dir.as_deref() {
+            match groups::load(dir) {
+                Ok(group_by) => app.group_by = group_by,
+                Err(e) => app.set_status(e),
+            }
+        }
         app.refresh();
         app
     }
 }
 
 impl App {
-    pub fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<Option<Vec<String>>> {
+    pub fn run<B>(&mut self, terminal: &mut Terminal<B>) -> io::Result<Option<Vec<String>>>
+    where
+        B: Backend<Error = io::Error> + io::Write,
+    {
         while !self.exit {
             terminal.draw(|frame| self.draw(frame))?;
             if let Some(res) = self.handle_events()? {
                 return Ok(Some(res));
             }
+            self.receive_loaded();
+            if self.action.title == "Sessions" {
+                self.load_all_details();
+            } else {
+                self.load_selected_detail();
+            }
+            self.update_preview();
+            if let Some(path) = self.pending_edit.take() {
+                self.edit_file(terminal, &path)?;
+            }
+            if let Some(text) = self.pending_yank.take() {
+                let backend = terminal.backend_mut();
+                backend.write_all(osc52(&text).as_bytes())?;
+                Backend::flush(backend)?;
+            }
         }
         Ok(None)
     }
 
+    /// Suspends the TUI, opens `path` in the user's editor and resumes with
+    /// freshly read files, keeping the current selection.
+    fn edit_file<B>(&mut self, terminal: &mut Terminal<B>, path: &Path) -> io::Result<()>
+    where
+        B: Backend<Error = io::Error> + io::Write,
+    {
+        let layout_key = self.selected_layout_file().map(KdlFile::layout_arg);
+
+        disable_raw_mode()?;
+        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+        let result = open_in_editor(path);
+        execute!(terminal.backend_mut(), EnterAlternateScreen)?;
+        enable_raw_mode()?;
+        terminal.clear()?;
+
+        self.reload_files(layout_key);
+
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let msg = match result {
+            Ok(status) if status.success() => {
+                match fs::read_to_string(path).map(|src| kdl::validate(&src)) {
+                    Ok(Ok(())) => format!("Edited {}", name),
+                    Ok(Err(problem)) => format!("{}: {}", name, problem),
+                    Err(e) => format!("{}: {}", name, e),
+                }
+            }
+            Ok(status) => format!("Editor exited with {}", status),
+            Err(e) => format!("Could not start editor: {}", e),
+        };
+        self.set_status(msg);
+        Ok(())
+    }
+
+    /// Queues the selected layout or config to be opened in `$EDITOR`.
+    fn request_edit(&mut self) {
+        if self.action.title != "New Session" {
+            return;
+        }
+        let file = match self.focused_list {
+            ListType::Config => self.selected_config_file(),
+            _ => self.selected_layout_file(),
+        };
+        match file {
+            Some(f) if f.builtin => self.set_status(format!(
+                "{} is built into zellij and can't be edited",
+                f.name
+            )),
+            Some(f) => self.pending_edit = Some(f.path.clone()),
+            None => self.set_status("Nothing to edit".to_string()),
+        }
+    }
+
+    /// Queues the selected session's name, or the command Enter would run
+    /// for it (`zellij attach <name>`, or the New Session command), for the
+    /// clipboard.
+    fn yank(&mut self, name_only: bool) {
+        let text = if self.action.title == "New Session" {
+            shell_command(&self.new_session_args())
+        } else {
+            let Some(name) = self.selected_session().map(|s| s.name.clone()) else {
+                self.set_status("Nothing to copy".to_string());
+                return;
+            };
+            if name_only {
+                name
+            } else {
+                shell_command(&["attach".to_string(), name])
+            }
+        };
+        self.set_status(format!("Copied to clipboard: {}", text));
+        self.pending_yank = Some(text);
+    }
+
+    /// Pins or unpins the highlighted session or layout, re-sorting its list
+    /// and saving the pins.
+    fn toggle_pin(&mut self) {
+        let (set, key) = if self.action.title == "New Session" {
+            if self.focused_list != ListType::Layout {
+                self.set_status("Only sessions and layouts can be pinned".to_string());
+                return;
+            }
+            let Some(key) = self.selected_layout_file().map(KdlFile::layout_arg) else {
+                return;
+            };
+            (&mut self.pins.layouts, key)
+        } else {
+            let Some(name) = self.selected_session().map(|s| s.name.clone()) else {
+                return;
+            };
+            (&mut self.pins.sessions, name)
+        };
+        if !set.remove(&key) {
+            set.insert(key.clone());
+        }
+
+        if self.action.title == "New Session" {
+            self.sort_layouts();
+            self.selected_layout = self.layouts.iter().position(|f| f.layout_arg() == key);
+        } else {
+            self.sort_sessions();
+        }
+        self.save_pins();
+    }
+
+    /// Carries a renamed layout's pin over to its new path.
+    fn move_layout_pin(&mut self, from: &Path, to: &Path) {
+        if self.pins.layouts.remove(from.to_string_lossy().as_ref()) {
+            self.pins.layouts.insert(to.to_string_lossy().to_string());
+            self.save_pins();
+        }
+    }
+
+    fn save_pins(&mut self) {
+        if let Some(dir) = self.data_dir.as_deref()
+            && let Err(e) = state::save_pins(dir, &self.pins)
+        {
+            self.set_status(format!("Could not save pins: {}", e));
+        }
+    }
+
+    fn set_status(&mut self, msg: String) {
+        self.status_message = msg;
+        self.input_mode = InputMode::Status(self.status_message.clone());
+    }
+
     fn draw(&self, frame: &mut ratatui::Frame) {
         frame.render_widget(self, frame.area());
     }
 
     fn handle_events(&mut self) -> io::Result<Option<Vec<String>>> {
+        // Wake up for a pending preview, details coming in, or to redraw
+        // ticking ages, even if no key is pressed
+        let tick = if self.detail_loader.is_busy() {
+            DETAIL_POLL
+        } else {
+            TICK
+        };
+        let timeout = self.preview_due.as_ref().map_or(tick, |&(_, due)| {
+            due.saturating_duration_since(Instant::now()).min(tick)
+        });
+        if !event::poll(timeout)? {
+            return Ok(None);
+        }
         match event::read()? {
             Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
                 Ok(self.handle_key_event(key_event))
@@ -182,6 +799,89 @@ impl App {
                     self.goto_buffer.clear();
                     None
                 }
+                KeyCode::Char('e') | KeyCode::Char('E') => {
+                    self.request_edit();
+                    None
+                }
+                KeyCode::Char('o') | KeyCode::Char('O') => {
+                    self.sort_mode = self.sort_mode.next();
+                    self.sort_sessions();
+                    if let Some(dir) = self.data_dir.as_deref()
+                        && let Err(e) = state::save_sort_mode(dir, self.sort_mode)
+                    {
+                        self.set_status(format!("Could not save sort mode: {}", e));
+                    }
+                    None
+                }
+                KeyCode::Char('v') | KeyCode::Char('V') => {
+                    if self.action.title == "Sessions" {
+                        self.input_mode = InputMode::Columns;
+                    } else {
+                        self.set_status("Columns apply to the Sessions view <L>".to_string());
+                    }
+                    None
+                }
+                KeyCode::Char('p') | KeyCode::Char('P') => {
+                    self.toggle_pin();
+                    None
+                }
+                KeyCode::Char('#') | KeyCode::Char('i') | KeyCode::Char('I')
+                    if self.action.title != "New Session" =>
+                {
+                    if let Some(name) = self.selected_session().map(|s| s.name.clone()) {
+                        let meta = self.session_meta.get(&name).cloned().unwrap_or_default();
+                        let (prompt, text) = if key_event.code == KeyCode::Char('#') {
+                            (Prompt::Tags, meta.tag_line())
+                        } else {
+                            (Prompt::Note, meta.note)
+                        };
+                        self.input_buffer = text;
+                        self.input_mode = InputMode::Input(prompt);
+                    }
+                    None
+                }
+                KeyCode::Char('f') | KeyCode::Char('F') if self.action.title != "New Session" => {
+                    self.input_buffer = state::tag_line(&self.tag_filter);
+                    self.input_mode = InputMode::Input(Prompt::TagFilter);
+                    None
+                }
+                KeyCode::Char('z') | KeyCode::Char('Z') => {
+                    self.toggle_grouped();
+                    None
+                }
+                KeyCode::Char(' ') => {
+                    self.toggle_group();
+                    None
+                }
+                KeyCode::Char('w') | KeyCode::Char('W') => {
+                    self.show_timestamps = !self.show_timestamps;
+                    None
+                }
+                KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    self.yank(key_event.code == KeyCode::Char('Y'));
+                    None
+                }
+                KeyCode::Char('c') | KeyCode::Char('C') if self.action.title != "New Session" => {
+                    if let Some(name) = self.selected_session().map(|s| s.name.clone()) {
+                        self.input_buffer = format!("{}-clone", name);
+                        self.input_mode = InputMode::Input(Prompt::CloneSession);
+                    }
+                    None
+                }
+                KeyCode::Char('s') | KeyCode::Char('S') => {
+                    if let Some(name) = self.selected_session().map(|s| s.name.clone()) {
+                        self.input_buffer = name;
+                        self.input_mode = InputMode::Input(Prompt::SaveSessionLayout);
+                    }
+                    None
+                }
+                KeyCode::Char(c)
+                    if self.action.title == "New Session"
+                        && matches!(c.to_ascii_lowercase(), 'c' | 'm' | 'x' | 'u' | 't') =>
+                {
+                    self.layout_file_key(c.to_ascii_lowercase());
+                    None
+                }
                 KeyCode::Tab => {
                     self.next_action();
                     None
@@ -194,6 +894,10 @@ impl App {
                     self.focused_list = match self.focused_list {
                         ListType::Layout => ListType::Config,
                         ListType::Config => ListType::Layout,
+                        ListType::Session => {
+                            self.enter_tab_list();
+                            self.focused_list
+                        }
                         _ => self.focused_list,
                     };
                     None
@@ -202,10 +906,15 @@ impl App {
                     self.focused_list = match self.focused_list {
                         ListType::Layout => ListType::Config,
                         ListType::Config => ListType::Layout,
+                        ListType::Tab => ListType::Session,
                         _ => self.focused_list,
                     };
                     None
                 }
+                KeyCode::Esc if self.focused_list == ListType::Tab => {
+                    self.focused_list = ListType::Session;
+                    None
+                }
                 KeyCode::Up => {
                     self.previous_item();
                     None
@@ -223,6 +932,13 @@ impl App {
                     None
                 }
                 KeyCode::Enter => self.trigger_action(),
+                KeyCode::Char(c)
+                    if self.action.title == "Sessions"
+                        && matches!(c.to_ascii_lowercase(), 'a' | 'k' | 'd') =>
+                {
+                    let action = ACTIONS.into_iter().find(|&a| a == c.to_ascii_lowercase())?;
+                    self.row_action(action)
+                }
                 KeyCode::Char(c) if ACTIONS.iter().any(|&a| a == c.to_ascii_lowercase()) => {
                     self.do_action(key_event.code);
                     None
@@ -244,24 +960,33 @@ impl App {
                 }
                 KeyCode::Enter => {
                     if let Ok(index) = self.goto_buffer.parse::<usize>() {
-                        if index > 0 {
-                            if self.action.title == "New Session" {
-                                let list_len = match self.focused_list {
-                                    ListType::Layout => self.layouts.len(),
-                                    ListType::Config => self.configs.len(),
-                                    _ => 0,
-                                };
-                                let selected = (index - 1).min(list_len.saturating_sub(1));
-                                match self.focused_list {
-                                    ListType::Layout => self.selected_layout = selected,
-                                    ListType::Config => self.selected_config = selected,
-                                    _ => {}
-                                }
+                        if self.action.title == "New Session" {
+                            let list_len = match self.focused_list {
+                                ListType::Layout => self.layouts.len(),
+                                ListType::Config => self.configs.len(),
+                                _ => 0,
+                            };
+                            // Index 0 is the "(none / zellij default)" entry
+                            let selected = if index == 0 || list_len == 0 {
+                                None
                             } else {
-                                let visible_count = self.get_visible_sessions().len();
-                                self.selected_session =
-                                    (index - 1).min(visible_count.saturating_sub(1));
+                                Some((index - 1).min(list_len - 1))
+                            };
+                            match self.focused_list {
+                                ListType::Layout => self.selected_layout = selected,
+                                ListType::Config => {
+                                    self.selected_config = selected;
+                                    self.preselect_layout();
+                                }
+                                _ => {}
+                            }
+                        } else if index > 0 {
+                            if self.focused_list == ListType::Tab {
+                                self.focused_list = ListType::Session;
                             }
+                            let visible_count = self.session_rows().len();
+                            self.selected_session =
+                                (index - 1).min(visible_count.saturating_sub(1));
                         }
                     }
                     self.input_mode = InputMode::Normal;
@@ -273,40 +998,524 @@ impl App {
                 self.input_mode = InputMode::Normal;
                 None
             }
+            InputMode::Input(prompt) => match key_event.code {
+                KeyCode::Char(c) => {
+                    self.input_buffer.push(c);
+                    None
+                }
+                KeyCode::Backspace => {
+                    self.input_buffer.pop();
+                    None
+                }
+                KeyCode::Esc => {
+                    self.input_mode = InputMode::Normal;
+                    None
+                }
+                KeyCode::Enter => {
+                    self.input_mode = InputMode::Normal;
+                    match prompt {
+                        Prompt::CloneSession => self.clone_session(),
+                        Prompt::Tags | Prompt::Note | Prompt::TagFilter => {
+                            self.submit_meta_prompt(prompt);
+                            None
+                        }
+                        _ => {
+                            self.submit_prompt(prompt);
+                            None
+                        }
+                    }
+                }
+                _ => None,
+            },
+            InputMode::Confirm(confirm) => {
+                self.input_mode = InputMode::Normal;
+                if matches!(key_event.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                    self.run_confirmed(confirm);
+                } else {
+                    self.set_status("Cancelled".to_string());
+                }
+                None
+            }
+            InputMode::Columns => {
+                self.column_picker_key(key_event.code);
+                None
+            }
         }
     }
 
-    fn trigger_action(&mut self) -> Option<Vec<String>> {
-        if self.action.title == "New Session" {
-            let mut args = Vec::new();
-            if !self.layouts.is_empty() {
-                let layout_path = self.layouts[self.selected_layout]
-                    .path
-                    .to_string_lossy()
-                    .to_string();
-                args.push("-l".to_string());
-                args.push(layout_path);
-            }
-            if !self.configs.is_empty() {
-                let config_path = self.configs[self.selected_config]
-                    .path
-                    .to_string_lossy()
-                    .to_string();
-                args.push("-c".to_string());
-                args.push(config_path);
+    fn column_picker_key(&mut self, code: KeyCode) {
+        let last = self.columns.len().saturating_sub(1);
+        let cursor = self.column_cursor.min(last);
+        match code {
+            KeyCode::Up => self.column_cursor = cursor.saturating_sub(1),
+            KeyCode::Down => self.column_cursor = (cursor + 1).min(last),
+            KeyCode::Char(' ') => {
+                if let Some(column) = self.columns.get_mut(cursor) {
+                    column.shown = !column.shown;
+                }
+            }
+            KeyCode::Char('<') if cursor > 0 => {
+                self.columns.swap(cursor, cursor - 1);
+                self.column_cursor = cursor - 1;
+            }
+            KeyCode::Char('>') if cursor < last => {
+                self.columns.swap(cursor, cursor + 1);
+                self.column_cursor = cursor + 1;
             }
+            KeyCode::Char(c @ ('+' | '-')) => {
+                if let Some(column) = self.columns.get_mut(cursor) {
+                    let width = column.width().unwrap_or(20);
+                    column.width = Some(if c == '+' {
+                        (width + 1).min(columns::MAX_WIDTH)
+                    } else {
+                        width.saturating_sub(1).max(1)
+                    });
+                }
+            }
+            KeyCode::Char('0') => {
+                if let Some(column) = self.columns.get_mut(cursor) {
+                    column.width = None;
+                }
+            }
+            KeyCode::Char('s') | KeyCode::Char('S') => {
+                let result = self
+                    .config_dir
+                    .as_deref()
+                    .ok_or_else(|| io::Error::other("no config directory"))
+                    .and_then(|dir| columns::save(dir, &self.columns));
+                match result {
+                    Ok(path) => self.set_status(format!("Saved columns to {}", path.display())),
+                    Err(e) => self.set_status(format!("Could not save columns: {}", e)),
+                }
+            }
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('v') | KeyCode::Char('V') => {
+                self.input_mode = InputMode::Normal
+            }
+            _ => {}
+        }
+    }
+
+    /// Text shown in the status area while a prompt or confirmation is open.
+    pub(crate) fn prompt_line(&self) -> Option<String> {
+        let selected = self
+            .selected_layout_file()
+            .map(|f| f.name.as_str())
+            .unwrap_or_default();
+        match self.input_mode {
+            InputMode::Input(Prompt::DuplicateLayout) => Some(format!(
+                "Duplicate {} as: {}_  (Enter to confirm, Esc to cancel)",
+                selected, self.input_buffer
+            )),
+            InputMode::Input(Prompt::RenameLayout) => Some(format!(
+                "Rename {} to: {}_  (Enter to confirm, Esc to cancel)",
+                selected, self.input_buffer
+            )),
+            InputMode::Input(Prompt::NewLayout(template)) => Some(format!(
+                "New layout from \"{}\" template, name: {}_  (Enter to confirm, Esc to cancel)",
+                template, self.input_buffer
+            )),
+            InputMode::Input(Prompt::SaveSessionLayout) => Some(format!(
+                "Save layout of {} as: {}_  (Enter to confirm, Esc to cancel)",
+                self.selected_session()
+                    .map(|s| s.name.as_str())
+                    .unwrap_or_default(),
+                self.input_buffer
+            )),
+            InputMode::Input(Prompt::CloneSession) => Some(format!(
+                "Clone {} into new session: {}_  (Enter to start, Esc to cancel)",
+                self.selected_session()
+                    .map(|s| s.name.as_str())
+                    .unwrap_or_default(),
+                self.input_buffer
+            )),
+            InputMode::Input(Prompt::Tags) => Some(format!(
+                "Tags of {}: {}_  (space separated, Enter to save, Esc to cancel)",
+                self.selected_session()
+                    .map(|s| s.name.as_str())
+                    .unwrap_or_default(),
+                self.input_buffer
+            )),
+            InputMode::Input(Prompt::Note) => Some(format!(
+                "Note on {}: {}_  (Enter to save, Esc to cancel)",
+                self.selected_session()
+                    .map(|s| s.name.as_str())
+                    .unwrap_or_default(),
+                self.input_buffer
+            )),
+            InputMode::Input(Prompt::TagFilter) => Some(format!(
+                "Show sessions tagged with all of: {}_  (empty shows all, Esc to cancel)",
+                self.input_buffer
+            )),
+            InputMode::Confirm(Confirm::TrashLayout) => {
+                Some(format!("Move {} to the zellui trash? (y/n)", selected))
+            }
+            InputMode::Confirm(Confirm::RestoreLayout) => {
+                Some("Restore the most recently trashed layout? (y/n)".to_string())
+            }
+            InputMode::Confirm(confirm @ (Confirm::KillGroup | Confirm::DeleteGroup)) => {
+                let kill = confirm == Confirm::KillGroup;
+                let (group, targets) = self.group_targets(kill)?;
+                Some(format!(
+                    "{} {} session(s) in {}: {}? (y/n)",
+                    if kill { "Kill" } else { "Delete" },
+                    targets.len(),
+                    group,
+                    targets.join(", ")
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    /// Layout list operations: (c)opy, (m)ove/rename, (x) trash, (u)ndo
+    /// trash and (t)emplate.
+    fn layout_file_key(&mut self, key: char) {
+        if self.focused_list != ListType::Layout {
+            self.set_status("Switch to the layout list to manage layouts".to_string());
+            return;
+        }
+        let selected = self
+            .selected_layout_file()
+            .filter(|f| !f.builtin)
+            .map(|f| f.layout_name().to_string());
+
+        match (key, selected) {
+            ('c', Some(name)) => {
+                self.input_buffer = format!("{}-copy", name);
+                self.input_mode = InputMode::Input(Prompt::DuplicateLayout);
+            }
+            ('m', Some(name)) => {
+                self.input_buffer = name;
+                self.input_mode = InputMode::Input(Prompt::RenameLayout);
+            }
+            ('x', Some(_)) => self.input_mode = InputMode::Confirm(Confirm::TrashLayout),
+            ('c' | 'm' | 'x', None) => {
+                self.set_status("Select a layout file first".to_string());
+            }
+            ('u', _) => self.input_mode = InputMode::Confirm(Confirm::RestoreLayout),
+            ('t', _) => {
+                let template = self
+                    .selected_layout_file()
+                    .filter(|f| f.builtin)
+                    .and_then(|f| BUILTIN_LAYOUTS.iter().find(|(name, _)| *name == f.name))
+                    .map_or("default", |(name, _)| *name);
+                self.input_buffer.clear();
+                self.input_mode = InputMode::Input(Prompt::NewLayout(template));
+            }
+            _ => {}
+        }
+    }
+
+    /// Saves the tags or note typed for the selected session, or applies the
+    /// tag filter.
+    fn submit_meta_prompt(&mut self, prompt: Prompt) {
+        let input = self.input_buffer.trim().to_string();
+        if prompt == Prompt::TagFilter {
+            let selected = self.selected_session().map(|s| s.name.clone());
+            self.tag_filter = state::parse_tags(&input);
+            self.clamp_selection();
+            if let Some(name) = selected {
+                self.select_session_by_name(&name);
+            }
+            return;
+        }
+        let Some(name) = self.selected_session().map(|s| s.name.clone()) else {
+            return;
+        };
+        let meta = self.session_meta.entry(name.clone()).or_default();
+        if prompt == Prompt::Tags {
+            meta.tags = state::parse_tags(&input);
+        } else {
+            meta.note = input;
+        }
+        if meta.is_empty() {
+            self.session_meta.remove(&name);
+        }
+        self.save_session_meta();
+        // A retagged session may no longer match the filter
+        self.clamp_selection();
+    }
+
+    /// Drops what zellui keeps about deleted sessions: their attach history,
+    /// pins, tags and notes.
+    fn forget_sessions(&mut self, names: &[String]) {
+        if let Some(dir) = self.data_dir.as_deref()
+            && let Ok(history) = state::forget_attached(dir, names)
+        {
+            self.last_attached = history;
+        }
+        let pinned = self.pins.sessions.len();
+        for name in names {
+            self.pins.sessions.remove(name);
+        }
+        if self.pins.sessions.len() != pinned {
+            self.save_pins();
+        }
+        let before = self.session_meta.len();
+        for name in names {
+            self.session_meta.remove(name);
+        }
+        if self.session_meta.len() != before {
+            self.save_session_meta();
+        }
+    }
+
+    fn save_session_meta(&mut self) {
+        if let Some(dir) = self.data_dir.as_deref()
+            && let Err(e) = state::save_session_meta(dir, &self.session_meta)
+        {
+            self.set_status(format!("Could not save tags and notes: {}", e));
+        }
+    }
+
+    fn submit_prompt(&mut self, prompt: Prompt) {
+        let name = match layout_files::layout_file_name(&self.input_buffer) {
+            Ok(name) => name,
+            Err(msg) => {
+                self.set_status(msg);
+                return;
+            }
+        };
+        let selected = self.selected_layout_file().map(|f| f.path.clone());
+
+        let (result, verb) = match (prompt, selected) {
+            (Prompt::DuplicateLayout, Some(path)) => {
+                (layout_files::duplicate(&path, &name), "Duplicated as")
+            }
+            (Prompt::RenameLayout, Some(path)) => {
+                let result = layout_files::rename(&path, &name);
+                if let Ok(ref renamed) = result {
+                    self.move_layout_pin(&path, renamed);
+                }
+                (result, "Renamed to")
+            }
+            (Prompt::NewLayout(template), _) => match get_zellij_layout_dir() {
+                Some(dir) => (
+                    layout_files::create_from_template(
+                        &dir,
+                        &name,
+                        template,
+                        &layout_files::builtin_template(template),
+                    ),
+                    "Created",
+                ),
+                None => {
+                    self.set_status("Could not locate the zellij config directory".to_string());
+                    return;
+                }
+            },
+            (Prompt::SaveSessionLayout, _) => {
+                let (Some(session), Some(dir)) =
+                    (self.selected_session().cloned(), get_zellij_layout_dir())
+                else {
+                    return;
+                };
+                let result = zellij::session_layout(&session).and_then(|layout| {
+                    layout_files::save_session_layout(&dir, &name, &session.name, &layout)
+                });
+                (result, "Saved layout as")
+            }
+            _ => return,
+        };
+        self.finish_layout_op(result, verb);
+    }
+
+    fn run_confirmed(&mut self, confirm: Confirm) {
+        let kill = match confirm {
+            Confirm::KillGroup => true,
+            Confirm::DeleteGroup => false,
+            Confirm::TrashLayout | Confirm::RestoreLayout => {
+                return self.run_layout_confirmed(confirm);
+            }
+        };
+        self.remove_group(kill);
+    }
+
+    fn run_layout_confirmed(&mut self, confirm: Confirm) {
+        let Some(trash_dir) = self.data_dir.as_ref().map(|d| d.join("trash")) else {
+            self.set_status("Could not locate the zellui data directory".to_string());
+            return;
+        };
+        match confirm {
+            Confirm::TrashLayout => {
+                let Some(path) = self.selected_layout_file().map(|f| f.path.clone()) else {
+                    return;
+                };
+                let previous = self.selected_layout;
+                match layout_files::trash(&path, &trash_dir) {
+                    Ok(_) => {
+                        self.reload_files(None);
+                        self.selected_layout = previous;
+                        self.clamp_selection();
+                        self.set_status(format!(
+                            "Moved {} to the trash (<U> to restore)",
+                            path.file_name().unwrap_or_default().to_string_lossy()
+                        ));
+                    }
+                    Err(e) => self.set_status(format!("Error: {}", e)),
+                }
+            }
+            Confirm::RestoreLayout => {
+                let Some(layout_dir) = get_zellij_layout_dir() else {
+                    return;
+                };
+                match layout_files::restore_latest(&trash_dir, &layout_dir) {
+                    Ok(Some(path)) => self.finish_layout_op(Ok(path), "Restored"),
+                    Ok(None) => self.set_status("The trash is empty".to_string()),
+                    Err(e) => self.set_status(format!("Error: {}", e)),
+                }
+            }
+            Confirm::KillGroup | Confirm::DeleteGroup => {}
+        }
+    }
+
+    /// Reloads the lists after a layout file operation and selects the result.
+    fn finish_layout_op(&mut self, result: io::Result<PathBuf>, verb: &str) {
+        match result {
+            Ok(path) => {
+                self.reload_files(Some(path.to_string_lossy().to_string()));
+                self.set_status(format!(
+                    "{} {}",
+                    verb,
+                    path.file_name().unwrap_or_default().to_string_lossy()
+                ));
+            }
+            Err(e) => self.set_status(format!("Error: {}", e)),
+        }
+    }
+
+    /// The selected layout and config, as passed to zellij.
+    fn selected_layout_and_config(&self) -> (Option<String>, Option<String>) {
+        (
+            self.selected_layout_file().map(KdlFile::layout_arg),
+            self.selected_config_file()
+                .map(|f| f.path.to_string_lossy().to_string()),
+        )
+    }
+
+    /// Arguments for starting a new session with the selected layout and
+    /// config.
+    fn new_session_args(&self) -> Vec<String> {
+        let (layout, config) = self.selected_layout_and_config();
+        zellij::new_session_args(None, layout.as_deref(), config.as_deref())
+    }
+
+    /// Starts a session from `layout` and `config`. Inside zellij, running
+    /// zellij in zellui's pane would nest it, so the session is created in
+    /// the background, under a free name if none is given, and switched to.
+    /// That takes two zellij runs, so `--print` refuses it.
+    fn start_session(
+        &mut self,
+        name: Option<String>,
+        layout: Option<String>,
+        config: Option<String>,
+    ) -> Option<Vec<String>> {
+        if self.current_session.is_none() {
             self.exit = true;
-            Some(args)
+            return Some(zellij::new_session_args(
+                name.as_deref(),
+                layout.as_deref(),
+                config.as_deref(),
+            ));
+        }
+        if self.print_only {
+            self.set_status(
+                "New sessions can't be printed from inside zellij; run zellui without --print"
+                    .to_string(),
+            );
+            return None;
+        }
+        let name = name.unwrap_or_else(|| self.free_session_name(layout.as_deref()));
+        if let Err(e) =
+            zellij::create_background_session(&name, layout.as_deref(), config.as_deref())
+        {
+            self.set_status(format!("Could not start {}: {}", name, e));
+            return None;
+        }
+        self.exit = true;
+        Some(vec![
+            "action".to_string(),
+            "switch-session".to_string(),
+            name,
+        ])
+    }
+
+    /// A session name that isn't taken, after the layout: `dev`, `dev-2`, ...
+    pub(crate) fn free_session_name(&self, layout: Option<&str>) -> String {
+        let base = layout
+            .and_then(|l| Path::new(l).file_stem())
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| "session".to_string());
+        let taken = |name: &String| self.sessions.iter().any(|s| &s.name == name);
+        std::iter::once(base.clone())
+            .chain((2..).map(|n| format!("{}-{}", base, n)))
+            .find(|name| !taken(name))
+            .unwrap_or(base)
+    }
+
+    /// Starts a session named after the prompt input from the selected
+    /// session's layout, saved without its session-specific bits in the
+    /// zellui data dir. zellij's own config applies, whatever was last picked
+    /// in the New Session view.
+    fn clone_session(&mut self) -> Option<Vec<String>> {
+        let new_name = self.input_buffer.trim().to_string();
+        if new_name.is_empty() {
+            self.set_status("Session name cannot be empty".to_string());
+            return None;
+        }
+        if self.sessions.iter().any(|s| s.name == new_name) {
+            self.set_status(format!("A session named {} already exists", new_name));
+            return None;
+        }
+        let session = self.selected_session()?.clone();
+
+        let Some(clone_dir) = self.data_dir.as_ref().map(|d| d.join("clones")) else {
+            self.set_status("Could not locate the zellui data directory".to_string());
+            return None;
+        };
+        let path = match zellij::session_layout(&session)
+            .and_then(|layout| layout_files::write_clone_layout(&clone_dir, &new_name, &layout))
+        {
+            Ok(path) => path,
+            Err(e) => {
+                self.set_status(format!("Could not clone {}: {}", session.name, e));
+                return None;
+            }
+        };
+
+        self.start_session(
+            Some(new_name),
+            Some(path.to_string_lossy().to_string()),
+            None,
+        )
+    }
+
+    fn trigger_action(&mut self) -> Option<Vec<String>> {
+        if matches!(self.selected_row(), Some(SessionRow::Group { .. })) {
+            match self.action.title {
+                "Kill" | "Delete" => self.confirm_group(self.action.title == "Kill"),
+                _ => self.toggle_group(),
+            }
+            return None;
+        }
+        match self.action.title {
+            // The unified view attaches on Enter; other actions use their keys
+            "Sessions" => self.act_on_selected("Attach"),
+            title => self.act_on_selected(title),
+        }
+    }
+
+    /// Runs the action called `title` on the highlighted session, or starts
+    /// a new session.
+    fn act_on_selected(&mut self, title: &str) -> Option<Vec<String>> {
+        if title == "New Session" {
+            let (layout, config) = self.selected_layout_and_config();
+            self.start_session(None, layout, config)
         } else {
-            let session_name = {
-                let visible_sessions = self.get_visible_sessions();
-                visible_sessions
-                    .get(self.selected_session)
-                    .map(|s| s.name.clone())
-            };
+            let session_name = self.selected_session().map(|s| s.name.clone());
 
             if let Some(name) = session_name {
-                match self.action.title {
+                match title {
                     "Kill" => {
                         let output = Command::new("zellij")
                             .args(["kill-session", &name])
@@ -316,6 +1525,7 @@ impl App {
                             Ok(result) => {
                                 let msg = if result.status.success() {
                                     self.sessions.retain(|s| s.name != name);
+                                    self.session_details.remove(&name);
                                     self.clamp_selection();
                                     String::from_utf8_lossy(&result.stdout).trim().to_string()
                                 } else {
@@ -336,8 +1546,27 @@ impl App {
                         None
                     }
                     "Attach" => {
+                        if self.current_session.as_deref() == Some(name.as_str()) {
+                            self.set_status(format!("Already in session {}", name));
+                            return None;
+                        }
+                        if self.focused_list == ListType::Tab
+                            && let Some(tab) = self.selected_tab_info().map(|t| t.name.clone())
+                        {
+                            let clients = zellij::client_count(&name).unwrap_or(0);
+                            self.pending_tab_focus = Some((name.clone(), tab, clients));
+                        }
                         self.exit = true;
-                        Some(vec!["attach".to_string(), name])
+                        if self.current_session.is_some() {
+                            // Nesting an attach inside a pane is a mess; switch instead
+                            Some(vec![
+                                "action".to_string(),
+                                "switch-session".to_string(),
+                                name,
+                            ])
+                        } else {
+                            Some(vec!["attach".to_string(), name])
+                        }
                     }
                     "Delete" => {
                         let output = Command::new("zellij")
@@ -348,6 +1577,8 @@ impl App {
                             Ok(result) => {
                                 let msg = if result.status.success() {
                                     self.sessions.retain(|s| s.name != name);
+                                    self.session_details.remove(&name);
+                                    self.forget_sessions(std::slice::from_ref(&name));
                                     self.clamp_selection();
                                     String::from_utf8_lossy(&result.stdout).trim().to_string()
                                 } else {
@@ -375,27 +1606,489 @@ impl App {
         }
     }
 
+    /// Why `action` can't be run on `session` in the unified Sessions view.
+    fn not_applicable(&self, action: &str, session: &Session) -> Option<String> {
+        match action {
+            "Kill" if session.exited => Some(format!("{} has already exited", session.name)),
+            "Kill" if self.current_session.as_ref() == Some(&session.name) => {
+                Some("Can't kill the session zellui is running in".to_string())
+            }
+            "Delete" if !session.exited => Some(format!(
+                "{} is still running; kill it before deleting",
+                session.name
+            )),
+            _ => None,
+        }
+    }
+
+    /// Handles an action shortcut in the unified Sessions view, running it on
+    /// the highlighted row when it applies.
+    fn row_action(&mut self, action: Action) -> Option<Vec<String>> {
+        if matches!(self.selected_row(), Some(SessionRow::Group { .. })) {
+            match action.title {
+                "Kill" | "Delete" => self.confirm_group(action.title == "Kill"),
+                _ => self.toggle_group(),
+            }
+            return None;
+        }
+        let session = self.selected_session()?.clone();
+        if let Some(reason) = self.not_applicable(action.title, &session) {
+            self.set_status(reason);
+            return None;
+        }
+        self.act_on_selected(action.title)
+    }
+
     fn exit(&mut self) {
         self.exit = true;
     }
 
+    /// The session a zellij invocation built by zellui attaches to or creates,
+    /// when it names one.
+    pub fn session_from_args(args: &[String]) -> Option<String> {
+        match args {
+            [attach, name, ..] if attach == "attach" => Some(name.clone()),
+            [action, switch, name, ..] if action == "action" && switch == "switch-session" => {
+                Some(name.clone())
+            }
+            _ => args
+                .iter()
+                .position(|a| a == "-s")
+                .and_then(|i| args.get(i + 1).cloned()),
+        }
+    }
+
+    /// Gets the TUI ready to run again after zellij returned in launcher loop
+    /// mode, with fresh state and the session just used highlighted.
+    pub fn resume(&mut self, args: &[String], status: io::Result<std::process::ExitStatus>) {
+        self.exit = false;
+        self.input_mode = InputMode::Normal;
+        self.action = ACTIONS[0];
+        self.focused_list = ListType::Session;
+        self.refresh();
+
+        // An unnamed new session gets a random name; it's the youngest one
+        let last = Self::session_from_args(args).or_else(|| {
+            self.sessions
+                .iter()
+                .filter(|s| !s.exited)
+                .max_by_key(|s| s.created)
+                .map(|s| s.name.clone())
+        });
+        if let Some(name) = last {
+            self.select_session_by_name(&name);
+        }
+
+        match status {
+            Ok(status) if status.success() => {}
+            Ok(status) => self.set_status(format!("zellij exited with {}", status)),
+            Err(e) => self.set_status(format!("Could not run zellij: {}", e)),
+        }
+    }
+
+    /// Hands a tab picked to attach to over to the helper that focuses it
+    /// once zellij has attached. Only for zellij runs zellui starts itself;
+    /// `--print` drops it, as the shell may never run the command.
+    pub fn start_tab_focus(&mut self) {
+        if let Some((session, tab, clients)) = self.pending_tab_focus.take() {
+            let _ = zellij::spawn_tab_focus(&session, &tab, clients);
+        }
+    }
+
+    /// Notes the session a zellij invocation attaches to or creates, for
+    /// the "recently attached" sort.
+    pub fn record_attach(&mut self, args: &[String]) {
+        if let Some(name) = Self::session_from_args(args)
+            && let Some(dir) = self.data_dir.as_deref()
+            && let Ok(history) = state::record_attach(dir, &name, SystemTime::now())
+        {
+            self.last_attached = history;
+        }
+    }
+
+    /// Orders `sessions` by `sort_mode`, keeping the highlighted session
+    /// selected.
+    pub(crate) fn sort_sessions(&mut self) {
+        let selected = self.selected_session().map(|s| s.name.clone());
+        let current = self.current_session.clone();
+        let attached = &self.last_attached;
+        match self.sort_mode {
+            SortMode::Age => self.sessions.sort_by_key(|s| Reverse(s.created)),
+            SortMode::Name => self.sessions.sort_by(|a, b| a.name.cmp(&b.name)),
+            SortMode::Status => self.sessions.sort_by_key(|s| {
+                let rank = if current.as_ref() == Some(&s.name) {
+                    0
+                } else if !s.exited {
+                    1
+                } else {
+                    2
+                };
+                (rank, s.name.clone())
+            }),
+            SortMode::Attached => self.sessions.sort_by(|a, b| {
+                attached
+                    .get(&b.name)
+                    .cmp(&attached.get(&a.name))
+                    .then(b.created.cmp(&a.created))
+            }),
+        }
+        // Stable, so pinned sessions keep the mode's order among themselves
+        let pinned = &self.pins.sessions;
+        self.sessions.sort_by_key(|s| !pinned.contains(&s.name));
+        match selected {
+            Some(name) => self.select_session_by_name(&name),
+            None => self.clamp_selection(),
+        }
+    }
+
+    /// The rows of the session list: the visible sessions, clustered under
+    /// group headers in the grouped view. A group sits where its first
+    /// member would, and collapsed groups hide their members.
+    pub(crate) fn session_rows(&self) -> Vec<SessionRow<'_>> {
+        let visible = self.get_visible_sessions();
+        if !self.grouped {
+            return visible.into_iter().map(SessionRow::Session).collect();
+        }
+        let mut order: Vec<Result<String, &Session>> = Vec::new();
+        let mut members: HashMap<String, Vec<&Session>> = HashMap::new();
+        for session in visible {
+            match self.group_by.key(&session.name) {
+                Some(key) => {
+                    if !members.contains_key(&key) {
+                        order.push(Ok(key.clone()));
+                    }
+                    members.entry(key).or_default().push(session);
+                }
+                None => order.push(Err(session)),
+            }
+        }
+
+        let mut rows = Vec::new();
+        for entry in order {
+            match entry {
+                Ok(name) => {
+                    let sessions = members.remove(&name).unwrap_or_default();
+                    let collapsed = self.collapsed_groups.contains(&name);
+                    let shown = if collapsed {
+                        Vec::new()
+                    } else {
+                        sessions.clone()
+                    };
+                    rows.push(SessionRow::Group {
+                        name,
+                        sessions,
+                        collapsed,
+                    });
+                    rows.extend(shown.into_iter().map(SessionRow::Session));
+                }
+                Err(session) => rows.push(SessionRow::Session(session)),
+            }
+        }
+        rows
+    }
+
+    pub(crate) fn selected_row(&self) -> Option<SessionRow<'_>> {
+        if self.action.title == "New Session" {
+            return None;
+        }
+        self.session_rows().into_iter().nth(self.selected_session)
+    }
+
+    /// Switches between the flat and grouped session list, keeping the
+    /// highlighted session selected.
+    fn toggle_grouped(&mut self) {
+        let selected = self.selected_session().map(|s| s.name.clone());
+        self.grouped = !self.grouped;
+        if let Some(name) = selected {
+            if let Some(key) = self.group_by.key(&name) {
+                self.collapsed_groups.remove(&key);
+            }
+            self.select_session_by_name(&name);
+        }
+        self.clamp_selection();
+    }
+
+    /// Collapses or expands the highlighted group. On a session inside a
+    /// group, collapses that group and moves to its header.
+    fn toggle_group(&mut self) {
+        match self.selected_row() {
+            Some(SessionRow::Group {
+                name, collapsed, ..
+            }) => {
+                if collapsed {
+                    self.collapsed_groups.remove(&name);
+                } else {
+                    self.collapsed_groups.insert(name);
+                }
+            }
+            Some(SessionRow::Session(session)) if self.grouped => {
+                let Some(key) = self.group_by.key(&session.name) else {
+                    return;
+                };
+                let header = self
+                    .session_rows()
+                    .iter()
+                    .position(|row| matches!(row, SessionRow::Group { name, .. } if *name == key));
+                self.collapsed_groups.insert(key);
+                if let Some(idx) = header {
+                    self.selected_session = idx;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The highlighted group and the sessions a group kill (live ones, bar
+    /// the current session) or delete (exited ones) would remove.
+    fn group_targets(&self, kill: bool) -> Option<(String, Vec<String>)> {
+        let Some(SessionRow::Group { name, sessions, .. }) = self.selected_row() else {
+            return None;
+        };
+        let targets = sessions
+            .iter()
+            .filter(|s| {
+                if kill {
+                    !s.exited && self.current_session.as_ref() != Some(&s.name)
+                } else {
+                    s.exited
+                }
+            })
+            .map(|s| s.name.clone())
+            .collect();
+        Some((name, targets))
+    }
+
+    fn confirm_group(&mut self, kill: bool) {
+        match self.group_targets(kill) {
+            Some((group, targets)) if targets.is_empty() => self.set_status(format!(
+                "No {} sessions in {}",
+                if kill { "live" } else { "exited" },
+                group
+            )),
+            Some(_) => {
+                self.input_mode = InputMode::Confirm(if kill {
+                    Confirm::KillGroup
+                } else {
+                    Confirm::DeleteGroup
+                });
+            }
+            None => {}
+        }
+    }
+
+    /// Kills or deletes every target of the highlighted group.
+    fn remove_group(&mut self, kill: bool) {
+        let Some((group, targets)) = self.group_targets(kill) else {
+            return;
+        };
+        let subcommand = if kill {
+            "kill-session"
+        } else {
+            "delete-session"
+        };
+        let mut failures = Vec::new();
+        let mut removed = Vec::new();
+        for name in targets {
+            match Command::new("zellij").args([subcommand, &name]).output() {
+                Ok(output) if output.status.success() => {
+                    self.sessions.retain(|s| s.name != name);
+                    self.session_details.remove(&name);
+                    removed.push(name);
+                }
+                Ok(output) => failures.push(format!(
+                    "{}: {}",
+                    name,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )),
+                Err(e) => failures.push(format!("{}: {}", name, e)),
+            }
+        }
+        if !kill {
+            self.forget_sessions(&removed);
+        }
+        let verb = if kill { "Killed" } else { "Deleted" };
+        let mut msg = format!("{} {} session(s) in {}", verb, removed.len(), group);
+        if !failures.is_empty() {
+            msg = format!("{}; failed: {}", msg, failures.join(", "));
+        }
+        self.clamp_selection();
+        self.set_status(msg);
+        self.fetch_sessions();
+    }
+
+    /// Moves the selection to the named session if it's in the current view.
+    pub(crate) fn select_session_by_name(&mut self, name: &str) {
+        if let Some(idx) = self
+            .session_rows()
+            .iter()
+            .position(|row| matches!(row, SessionRow::Session(s) if s.name == name))
+        {
+            self.selected_session = idx;
+        }
+    }
+
     fn refresh(&mut self) {
+        self.session_details.clear();
+        self.server_memory = None;
+        self.detail_loader.reset();
+        self.preview = None;
         self.fetch_sessions();
         self.fetch_files();
+        self.load_selected_detail();
+    }
+
+    pub(crate) fn selected_session(&self) -> Option<&Session> {
+        match self.selected_row()? {
+            SessionRow::Session(session) => Some(session),
+            SessionRow::Group { .. } => None,
+        }
+    }
+
+    /// Takes in what the background loader has fetched since the last call.
+    pub(crate) fn receive_loaded(&mut self) {
+        for loaded in self.detail_loader.poll() {
+            match loaded {
+                zellij::Loaded::Detail(name, detail) => {
+                    self.session_details.insert(name, detail);
+                }
+                zellij::Loaded::Memory(memory) => self.server_memory = Some(memory),
+                zellij::Loaded::Screen(session, screen) => {
+                    self.preview = Some(match screen {
+                        Ok(screen) => ScreenPreview {
+                            session,
+                            lines: last_lines(&screen, PREVIEW_LINES),
+                            error: None,
+                        },
+                        Err(e) => ScreenPreview {
+                            session,
+                            lines: Vec::new(),
+                            error: Some(e.to_string()),
+                        },
+                    });
+                }
+            }
+        }
+    }
+
+    /// Schedules a screen preview when the highlighted live session changes
+    /// and asks the loader for it once the selection has rested for
+    /// `PREVIEW_DEBOUNCE`.
+    fn update_preview(&mut self) {
+        let wanted = self
+            .selected_session()
+            .filter(|s| !s.exited)
+            .map(|s| s.name.clone());
+        let Some(name) = wanted else {
+            self.preview = None;
+            self.preview_due = None;
+            return;
+        };
+        if self.preview.as_ref().is_some_and(|p| p.session == name)
+            || self.detail_loader.screen_pending() == Some(name.as_str())
+        {
+            return;
+        }
+        match self.preview_due {
+            Some((ref pending, due)) if *pending == name => {
+                if Instant::now() < due {
+                    return;
+                }
+                self.preview_due = None;
+                self.detail_loader.request_screen(&name);
+            }
+            _ => self.preview_due = Some((name, Instant::now() + PREVIEW_DEBOUNCE)),
+        }
+    }
+
+    pub(crate) fn selected_tab_info(&self) -> Option<&TabInfo> {
+        let session = self.selected_session()?;
+        self.session_details
+            .get(&session.name)?
+            .tabs
+            .get(self.selected_tab)
+    }
+
+    /// Moves focus from the session list into the tabs of the selected live
+    /// session, starting at the tab that is currently focused there.
+    fn enter_tab_list(&mut self) {
+        if !matches!(self.action.title, "Attach" | "Sessions") {
+            self.set_status("Tabs can only be picked to attach to".to_string());
+            return;
+        }
+        self.load_selected_detail();
+        let Some(session) = self.selected_session() else {
+            return;
+        };
+        if session.exited {
+            self.set_status("Tabs can only be picked for live sessions".to_string());
+            return;
+        }
+        let Some(detail) = self.session_details.get(&session.name) else {
+            self.set_status("Tabs are still loading".to_string());
+            return;
+        };
+        let tabs = detail.tabs.as_slice();
+        if tabs.is_empty() {
+            self.set_status("No tabs reported for this session".to_string());
+            return;
+        }
+        self.selected_tab = tabs.iter().position(|t| t.focused).unwrap_or(0);
+        self.focused_list = ListType::Tab;
+    }
+
+    /// Asks for the detail panel contents of the highlighted session, live or
+    /// from the resurrection cache, once per session until the next refresh.
+    fn load_selected_detail(&mut self) {
+        let Some(session) = self.selected_session() else {
+            return;
+        };
+        if !self.session_details.contains_key(&session.name) {
+            let session = session.clone();
+            self.detail_loader.request(&session);
+        }
+    }
+
+    /// Asks for details of every session, for the tab and client columns of
+    /// the unified Sessions view. The loader serves the latest request first,
+    /// so rows are queued bottom up and the highlighted one last.
+    fn load_all_details(&mut self) {
+        let selected = self.selected_session().map(|s| s.name.clone());
+        let missing: Vec<Session> = self
+            .sessions
+            .iter()
+            .rev()
+            .filter(|s| Some(&s.name) != selected.as_ref())
+            .filter(|s| !self.session_details.contains_key(&s.name))
+            .cloned()
+            .collect();
+        for session in &missing {
+            self.detail_loader.request(session);
+        }
+        self.load_selected_detail();
+
+        let memory_shown = self
+            .columns
+            .iter()
+            .any(|c| c.shown && c.kind == columns::ColumnKind::Memory);
+        if memory_shown && self.server_memory.is_none() {
+            self.detail_loader.request_memory();
+        }
     }
 
     fn fetch_sessions(&mut self) {
+        let selected = self.selected_session().map(|s| s.name.clone());
         let output = Command::new("zellij").args(["ls", "-n"]).output();
 
         if let Ok(output) = output {
             if output.status.success() {
+                let now = SystemTime::now();
                 let stdout = String::from_utf8_lossy(&output.stdout);
                 self.sessions = stdout
                     .lines()
                     .filter(|line| !line.is_empty())
                     .map(|line| {
                         let parts: Vec<&str> = line.split_whitespace().collect();
-                        let name = parts.get(0).unwrap_or(&"").to_string();
+                        let name = parts.first().unwrap_or(&"").to_string();
                         let exited = line.contains("EXITED");
 
                         let time_str = if let Some(start) = line.find("[Created ") {
@@ -408,13 +2101,22 @@ impl App {
                             ""
                         };
 
-                        let time = parse_time(time_str);
+                        let created = parse_time(time_str)
+                            .ok()
+                            .map(|age| now.checked_sub(age).unwrap_or(now));
 
-                        Session { name, time, exited }
+                        Session {
+                            name,
+                            created,
+                            exited,
+                        }
                     })
                     .collect();
 
-                self.clamp_selection();
+                self.sort_sessions();
+                if let Some(name) = selected {
+                    self.select_session_by_name(&name);
+                }
             } else {
                 self.sessions.clear();
             }
@@ -423,7 +2125,21 @@ impl App {
         }
     }
 
+    /// Re-reads layouts and configs, keeping the config selection and
+    /// selecting the layout whose `layout_arg` is `layout_key`.
+    fn reload_files(&mut self, layout_key: Option<String>) {
+        let config_key = self.selected_config_file().map(|f| f.path.clone());
+        self.fetch_files();
+        self.selected_layout =
+            layout_key.and_then(|k| self.layouts.iter().position(|f| f.layout_arg() == k));
+        self.selected_config =
+            config_key.and_then(|k| self.configs.iter().position(|f| f.path == k));
+    }
+
     fn fetch_files(&mut self) {
+        self.layouts.clear();
+        self.configs.clear();
+
         if let Some(config_dir) = get_zellij_config_dir() {
             // Layouts
             let mut layout_dir = config_dir.clone();
@@ -434,7 +2150,7 @@ impl App {
                 .map(|name| {
                     let mut path = layout_dir.clone();
                     path.push(&name);
-                    KdlFile { name, path }
+                    KdlFile::from_path(name, path)
                 })
                 .collect();
 
@@ -445,72 +2161,144 @@ impl App {
                 .map(|name| {
                     let mut path = config_dir.clone();
                     path.push(&name);
-                    KdlFile { name, path }
+                    KdlFile::from_path(name, path)
                 })
                 .collect();
+        }
 
-            // Select default.kdl if it exists
-            if let Some(idx) = self.layouts.iter().position(|f| f.name == "default.kdl") {
-                self.selected_layout = idx;
-            }
-            if let Some(idx) = self.configs.iter().position(|f| f.name == "default.kdl") {
-                self.selected_config = idx;
+        // Built-ins, unless a file in the layouts dir shadows them
+        for (name, description) in BUILTIN_LAYOUTS {
+            let file_name = format!("{}.kdl", name);
+            if !self.layouts.iter().any(|f| f.name == file_name) {
+                self.layouts.push(KdlFile::builtin(name, description));
             }
+        }
 
-            self.clamp_selection();
+        self.sort_layouts();
+
+        if let Some(idx) = self.configs.iter().position(|f| f.name == ZELLIJ_CONFIG) {
+            self.selected_config = Some(idx);
         }
+        self.preselect_layout();
+
+        self.clamp_selection();
+    }
+
+    /// Selects the layout the highlighted config would start with: its
+    /// `default_layout`, or zellij's own `default` when it doesn't set one.
+    /// With no config picked, zellij reads `config.kdl`, so that one counts.
+    /// A `default_layout` that isn't in the list leaves "(none)" selected,
+    /// which lets zellij apply it itself.
+    fn preselect_layout(&mut self) {
+        let config = self
+            .selected_config_file()
+            .or_else(|| self.configs.iter().find(|f| f.name == ZELLIJ_CONFIG));
+        let wanted = config
+            .and_then(|c| c.config.default_layout.clone())
+            .unwrap_or_else(|| "default".to_string());
+        self.selected_layout = self.find_layout(&wanted);
+    }
+
+    /// Resolves a `default_layout` value. Like zellij, a value with a `/` or
+    /// a `.kdl` extension is a path (relative ones to the layouts directory)
+    /// and anything else a layout name.
+    fn find_layout(&self, wanted: &str) -> Option<usize> {
+        if wanted.contains('/') || wanted.ends_with(".kdl") {
+            let path = match wanted.strip_prefix("~/") {
+                Some(rest) => PathBuf::from(std::env::var("HOME").ok()?).join(rest),
+                None => match get_zellij_layout_dir() {
+                    Some(dir) => dir.join(wanted),
+                    None => PathBuf::from(wanted),
+                },
+            };
+            return self
+                .layouts
+                .iter()
+                .position(|f| !f.builtin && f.path == path);
+        }
+        self.layouts.iter().position(|f| f.layout_name() == wanted)
     }
 
     pub(crate) fn get_visible_sessions(&self) -> Vec<&Session> {
+        let tagged = |s: &&Session| {
+            self.tag_filter.is_empty()
+                || self
+                    .session_meta
+                    .get(&s.name)
+                    .is_some_and(|m| self.tag_filter.iter().all(|tag| m.tags.contains(tag)))
+        };
+        let sessions = self.sessions.iter().filter(tagged);
         match self.action.title {
-            "Attach" | "Delete" => self.sessions.iter().filter(|s| s.exited).collect(),
-            "Kill" => self.sessions.iter().filter(|s| !s.exited).collect(),
-            _ => self.sessions.iter().collect(),
+            "Delete" => sessions.filter(|s| s.exited).collect(),
+            // Killing the session zellui runs in would kill zellui too
+            "Kill" => sessions
+                .filter(|s| !s.exited && self.current_session.as_ref() != Some(&s.name))
+                .collect(),
+            _ => sessions.collect(),
         }
     }
 
     fn clamp_selection(&mut self) {
-        let visible_count = self.get_visible_sessions().len();
+        let visible_count = self.session_rows().len();
         if visible_count == 0 {
             self.selected_session = 0;
         } else if self.selected_session >= visible_count {
             self.selected_session = visible_count.saturating_sub(1);
         }
 
-        if self.layouts.is_empty() {
-            self.selected_layout = 0;
-        } else if self.selected_layout >= self.layouts.len() {
-            self.selected_layout = self.layouts.len() - 1;
+        if let Some(idx) = self.selected_layout
+            && idx >= self.layouts.len()
+        {
+            self.selected_layout = self.layouts.len().checked_sub(1);
         }
 
-        if self.configs.is_empty() {
-            self.selected_config = 0;
-        } else if self.selected_config >= self.configs.len() {
-            self.selected_config = self.configs.len() - 1;
+        if let Some(idx) = self.selected_config
+            && idx >= self.configs.len()
+        {
+            self.selected_config = self.configs.len().checked_sub(1);
         }
     }
 
+    /// Moves pinned layouts to the top, keeping the order otherwise.
+    fn sort_layouts(&mut self) {
+        let pinned = &self.pins.layouts;
+        self.layouts
+            .sort_by_key(|f| !pinned.contains(&f.layout_arg()));
+    }
+
+    pub(crate) fn selected_layout_file(&self) -> Option<&KdlFile> {
+        self.selected_layout.and_then(|i| self.layouts.get(i))
+    }
+
+    pub(crate) fn selected_config_file(&self) -> Option<&KdlFile> {
+        self.selected_config.and_then(|i| self.configs.get(i))
+    }
+
     fn previous_item(&mut self) {
         if self.action.title == "New Session" {
             match self.focused_list {
                 ListType::Layout => {
-                    if self.layouts.is_empty() {
-                        return;
-                    }
                     self.selected_layout =
-                        (self.selected_layout + self.layouts.len() - 1) % self.layouts.len();
+                        cycle_optional(self.selected_layout, self.layouts.len(), false);
                 }
                 ListType::Config => {
-                    if self.configs.is_empty() {
-                        return;
-                    }
                     self.selected_config =
-                        (self.selected_config + self.configs.len() - 1) % self.configs.len();
+                        cycle_optional(self.selected_config, self.configs.len(), false);
+                    self.preselect_layout();
                 }
-                ListType::Session => {}
+                ListType::Session | ListType::Tab => {}
+            }
+        } else if self.focused_list == ListType::Tab {
+            let tab_count = self
+                .selected_session()
+                .and_then(|s| self.session_details.get(&s.name))
+                .map_or(0, |d| d.tabs.len());
+            if tab_count == 0 {
+                return;
             }
+            self.selected_tab = (self.selected_tab + tab_count - 1) % tab_count;
         } else {
-            let visible_count = self.get_visible_sessions().len();
+            let visible_count = self.session_rows().len();
             if visible_count == 0 {
                 return;
             }
@@ -522,21 +2310,27 @@ impl App {
         if self.action.title == "New Session" {
             match self.focused_list {
                 ListType::Layout => {
-                    if self.layouts.is_empty() {
-                        return;
-                    }
-                    self.selected_layout = (self.selected_layout + 1) % self.layouts.len();
+                    self.selected_layout =
+                        cycle_optional(self.selected_layout, self.layouts.len(), true);
                 }
                 ListType::Config => {
-                    if self.configs.is_empty() {
-                        return;
-                    }
-                    self.selected_config = (self.selected_config + 1) % self.configs.len();
+                    self.selected_config =
+                        cycle_optional(self.selected_config, self.configs.len(), true);
+                    self.preselect_layout();
                 }
-                ListType::Session => {}
+                ListType::Session | ListType::Tab => {}
+            }
+        } else if self.focused_list == ListType::Tab {
+            let tab_count = self
+                .selected_session()
+                .and_then(|s| self.session_details.get(&s.name))
+                .map_or(0, |d| d.tabs.len());
+            if tab_count == 0 {
+                return;
             }
+            self.selected_tab = (self.selected_tab + 1) % tab_count;
         } else {
-            let visible_count = self.get_visible_sessions().len();
+            let visible_count = self.session_rows().len();
             if visible_count == 0 {
                 return;
             }
@@ -545,10 +2339,10 @@ impl App {
     }
 
     fn page_up(&mut self) {
-        if self.action.title == "New Session" {
+        if self.action.title == "New Session" || self.focused_list == ListType::Tab {
             // Optional: page navigation for files
         } else {
-            let visible_count = self.get_visible_sessions().len();
+            let visible_count = self.session_rows().len();
             if visible_count == 0 {
                 return;
             }
@@ -557,10 +2351,10 @@ impl App {
     }
 
     fn page_down(&mut self) {
-        if self.action.title == "New Session" {
+        if self.action.title == "New Session" || self.focused_list == ListType::Tab {
             // Optional: page navigation for files
         } else {
-            let visible_count = self.get_visible_sessions().len();
+            let visible_count = self.session_rows().len();
             if visible_count == 0 {
                 return;
             }
@@ -594,14 +2388,18 @@ impl App {
     }
 
     fn do_action(&mut self, code: KeyCode) {
-        if let KeyCode::Char(c) = code {
-            if let Some(action) = ACTIONS
+        if let KeyCode::Char(c) = code
+            && let Some(action) = ACTIONS
                 .iter()
                 .find(|a| a.shortcut == c.to_ascii_lowercase())
-            {
-                self.action = *action;
-                self.clamp_selection();
+        {
+            self.action = *action;
+            if self.action.title == "New Session" {
+                self.focused_list = ListType::Layout;
+            } else {
+                self.focused_list = ListType::Session;
             }
+            self.clamp_selection();
         }
     }
 }
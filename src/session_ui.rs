@@ -1,50 +1,500 @@
+use std::time::SystemTime;
+
 use ratatui::{
     buffer::Buffer,
-    layout::Rect,
+    layout::{Constraint, Layout, Rect},
     style::Stylize,
-    widgets::{Block, List, ListItem, Paragraph, Widget},
+    text::Span,
+    text::{Line, Text},
+    widgets::{Block, Cell, Clear, List, ListItem, Paragraph, Row, Table, Widget, Wrap},
 };
 
-use crate::{App, InputMode};
+use crate::columns::{Column, ColumnKind};
+use crate::helpers::{format_age, format_kb, format_local_timestamp, truncate_to_width};
+use crate::{App, InputMode, ListType, PaneInfo, Session, SessionDetail, SessionRow, state};
+
+fn pane_line(pane: &PaneInfo) -> Line<'static> {
+    let what = match (&pane.command, &pane.plugin) {
+        (Some(command), _) => command.clone().into(),
+        (None, Some(plugin)) => format!("plugin {}", plugin).magenta(),
+        (None, None) => "(shell)".dark_gray(),
+    };
+    let mut spans = vec![
+        if pane.focused {
+            "  * ".yellow()
+        } else {
+            "    ".into()
+        },
+        what,
+    ];
+    if let Some(ref cwd) = pane.cwd {
+        spans.push("  ".into());
+        spans.push(cwd.clone().dark_gray());
+    }
+    Line::from(spans)
+}
+
+/// Tabs and panes of a session; `selected_tab` is highlighted while the tab
+/// list has focus.
+pub(crate) fn detail_lines(
+    detail: &SessionDetail,
+    selected_tab: Option<usize>,
+) -> Vec<Line<'static>> {
+    if let Some(ref error) = detail.error {
+        return vec![Line::from(error.clone().red())];
+    }
+    if detail.tabs.is_empty() {
+        return vec![Line::from("No tabs reported".dark_gray())];
+    }
+    let mut lines = Vec::new();
+    for (i, tab) in detail.tabs.iter().enumerate() {
+        let title = format!("{}. {}", i + 1, tab.name);
+        let title = if selected_tab == Some(i) {
+            title.white().bold().on_blue()
+        } else if tab.focused {
+            title.yellow().bold()
+        } else {
+            title.bold()
+        };
+        lines.push(Line::from(vec![
+            title,
+            format!("  ({} panes)", tab.panes.len()).dark_gray(),
+        ]));
+        lines.extend(tab.panes.iter().map(pane_line));
+    }
+    lines
+}
+
+fn render_session_detail(app: &App, area: Rect, buf: &mut Buffer) {
+    let Some(session) = app.selected_session() else {
+        return;
+    };
+    let mut lines = Vec::new();
+    if let Some(meta) = app.session_meta.get(&session.name) {
+        if !meta.tags.is_empty() {
+            lines.push(Line::from(meta.tag_line().cyan()));
+        }
+        if !meta.note.is_empty() {
+            lines.push(Line::from(meta.note.clone().italic()));
+        }
+    }
+    if session.exited {
+        lines.push(Line::from(
+            "Exited: layout zellij will resurrect".dark_gray().italic(),
+        ));
+    }
+    match app.session_details.get(&session.name) {
+        Some(detail) => {
+            let selected_tab = (app.focused_list == ListType::Tab).then_some(app.selected_tab);
+            lines.extend(detail_lines(detail, selected_tab));
+        }
+        None => lines.push(Line::from("Loading...".dark_gray())),
+    }
+
+    let mut block = Block::bordered().title(format!(" {} ", session.name).bold());
+    if app.focused_list == ListType::Tab {
+        block = block
+            .title_bottom(" <Enter> attach to tab  <Esc> back ".dark_gray())
+            .border_style(ratatui::style::Style::default().yellow());
+    } else if !session.exited && matches!(app.action.title, "Attach" | "Sessions") {
+        block = block.title_bottom(" <Right> pick a tab ".dark_gray());
+    }
+
+    Paragraph::new(Text::from(lines))
+        .wrap(Wrap { trim: false })
+        .block(block)
+        .render(area, buf);
+}
+
+fn render_screen_preview(app: &App, area: Rect, buf: &mut Buffer) {
+    let block = Block::bordered().title(" Screen ".bold());
+    let Some(session) = app.selected_session() else {
+        return;
+    };
+    let lines: Vec<Line> = match app.preview {
+        Some(ref preview) if preview.session == session.name => match preview.error {
+            Some(ref error) => vec![Line::from(error.clone().red())],
+            None => {
+                // Bottom-align: show as many trailing lines as fit
+                let rows = area.height.saturating_sub(2) as usize;
+                let start = preview.lines.len().saturating_sub(rows);
+                preview.lines[start..]
+                    .iter()
+                    .map(|line| Line::from(line.clone()))
+                    .collect()
+            }
+        },
+        _ => vec![Line::from("Loading...".dark_gray())],
+    };
+    Paragraph::new(Text::from(lines))
+        .block(block)
+        .render(area, buf);
+}
+
+/// The session's age, or its local creation time when timestamps are on.
+fn session_when(app: &App, session: &Session, now: SystemTime) -> String {
+    match (session.created, session.age_secs(now)) {
+        (Some(created), _) if app.show_timestamps => format_local_timestamp(created),
+        (_, Some(age)) => format!("{} ago", format_age(age)),
+        _ => "unknown age".to_string(),
+    }
+}
+
+/// Prefix for pinned sessions and layouts.
+const PIN_MARKER: &str = "★ ";
+
+/// `PIN_MARKER` when `pinned`, otherwise nothing.
+pub fn pin_marker(pinned: bool) -> &'static str {
+    if pinned { PIN_MARKER } else { "" }
+}
+
+/// One cell of the Sessions table, before truncation.
+fn column_cell(app: &App, kind: ColumnKind, session: &Session, now: SystemTime) -> Span<'static> {
+    let detail = app.session_details.get(&session.name);
+    let loading = || "…".to_string().into();
+    match kind {
+        ColumnKind::Name => format!(
+            "{}{}",
+            pin_marker(app.pins.sessions.contains(&session.name)),
+            session.name
+        )
+        .into(),
+        ColumnKind::Status => {
+            if session.exited {
+                "exited".dark_gray()
+            } else if app.current_session.as_ref() == Some(&session.name) {
+                "current".cyan()
+            } else {
+                "live".green()
+            }
+        }
+        ColumnKind::Age => match session.age_secs(now) {
+            Some(age) => format!("{} ago", format_age(age)).into(),
+            None => "unknown".dark_gray(),
+        },
+        ColumnKind::Created => match session.created {
+            Some(created) => format_local_timestamp(created).into(),
+            None => "unknown".dark_gray(),
+        },
+        ColumnKind::Tabs => detail.map_or_else(loading, |d| d.tabs.len().to_string().into()),
+        ColumnKind::Clients | ColumnKind::Memory if session.exited => "-".dark_gray(),
+        ColumnKind::Clients => match detail {
+            Some(d) => d.clients.map_or("?".into(), |n| n.to_string().into()),
+            None => loading(),
+        },
+        ColumnKind::Memory => match app.server_memory {
+            Some(ref memory) => memory
+                .get(&session.name)
+                .map_or("?".into(), |&kb| format_kb(kb).into()),
+            None => loading(),
+        },
+        ColumnKind::Tags => app
+            .session_meta
+            .get(&session.name)
+            .map(|m| m.tag_line())
+            .unwrap_or_default()
+            .cyan(),
+        ColumnKind::Notes => app
+            .session_meta
+            .get(&session.name)
+            .map(|m| m.note.clone())
+            .unwrap_or_default()
+            .into(),
+    }
+}
+
+/// Every session in one table, for the unified Sessions view, with the
+/// columns picked in `App::columns`.
+fn render_session_table(
+    app: &App,
+    sessions: &[SessionRow],
+    block: Block,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    const INDEX_WIDTH: u16 = 3;
+    let now = SystemTime::now();
+    let columns: Vec<&Column> = app.columns.iter().filter(|c| c.shown).collect();
+
+    // Columns without a width share what the others leave, so long names can
+    // be truncated to the space they really get
+    let inner = area.width.saturating_sub(2);
+    let fixed = columns
+        .iter()
+        .filter_map(|c| c.width())
+        .fold(INDEX_WIDTH + columns.len() as u16, u16::saturating_add);
+    let flexible = columns
+        .iter()
+        .filter(|c| c.width().is_none())
+        .count()
+        .max(1) as u16;
+    let share = (inner.saturating_sub(fixed) / flexible).max(1);
+    let widths: Vec<u16> = columns.iter().map(|c| c.width().unwrap_or(share)).collect();
+
+    let header =
+        Row::new(std::iter::once("#").chain(columns.iter().map(|c| c.kind.header()))).bold();
+    let rows = sessions.iter().enumerate().map(|(i, row)| {
+        let index = Cell::from((i + 1).to_string());
+        let row = match row {
+            SessionRow::Group {
+                name,
+                sessions,
+                collapsed,
+            } => {
+                let label = group_label(name, sessions.len(), *collapsed);
+                let width = widths.first().copied().unwrap_or_default() as usize;
+                Row::new([
+                    index,
+                    Cell::from(truncate_to_width(&label, width).magenta().bold()),
+                ])
+            }
+            SessionRow::Session(s) => {
+                let indent = if app.grouped && app.group_by.key(&s.name).is_some() {
+                    "  "
+                } else {
+                    ""
+                };
+                let cells = columns
+                    .iter()
+                    .zip(&widths)
+                    .enumerate()
+                    .map(|(c, (column, &width))| {
+                        let span = column_cell(app, column.kind, s, now);
+                        let text = if c == 0 {
+                            format!("{}{}", indent, span.content)
+                        } else {
+                            span.content.to_string()
+                        };
+                        Cell::from(Span::styled(
+                            truncate_to_width(&text, width as usize),
+                            span.style,
+                        ))
+                    });
+                Row::new(std::iter::once(index).chain(cells))
+            }
+        };
+        if i == app.selected_session {
+            row.white().bold().on_blue()
+        } else {
+            row
+        }
+    });
+    let constraints = std::iter::once(INDEX_WIDTH)
+        .chain(widths.iter().copied())
+        .map(Constraint::Length);
+    Widget::render(
+        Table::new(rows, constraints).header(header).block(block),
+        area,
+        buf,
+    );
+}
+
+/// `▾ api (3)` for an expanded group, `▸ api (3)` for a collapsed one.
+fn group_label(name: &str, count: usize, collapsed: bool) -> String {
+    let marker = if collapsed { "▸" } else { "▾" };
+    format!("{} {} ({})", marker, name, count)
+}
+
+/// Summary of the highlighted group in place of the session details.
+fn render_group_detail(app: &App, area: Rect, buf: &mut Buffer) {
+    let Some(SessionRow::Group { name, sessions, .. }) = app.selected_row() else {
+        return;
+    };
+    let live = sessions.iter().filter(|s| !s.exited).count();
+    let mut lines = vec![
+        Line::from(vec![
+            "Sessions: ".bold(),
+            format!(
+                "{} ({} live, {} exited)",
+                sessions.len(),
+                live,
+                sessions.len() - live
+            )
+            .into(),
+        ]),
+        Line::from(""),
+    ];
+    lines.extend(sessions.iter().map(|s| {
+        if s.exited {
+            Line::from(format!("  {} (exited)", s.name).dark_gray())
+        } else {
+            Line::from(format!("  {}", s.name))
+        }
+    }));
+    let hint = match app.action.title {
+        "Kill" => " <Enter> kill all live  <Space> collapse ",
+        "Delete" => " <Enter> delete all exited  <Space> collapse ",
+        "Sessions" => " <K>/<D> kill/delete group  <Space> collapse ",
+        _ => " <Enter>/<Space> collapse ",
+    };
+    Paragraph::new(Text::from(lines))
+        .block(
+            Block::bordered()
+                .title(format!(" Group {} ", name).bold())
+                .title_bottom(hint.dark_gray()),
+        )
+        .render(area, buf);
+}
+
+/// Popup listing every column kind: shown ones are checked, in table order.
+fn render_column_picker(app: &App, area: Rect, buf: &mut Buffer) {
+    let height = (app.columns.len() as u16 + 2).min(area.height);
+    let width = 36.min(area.width);
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+    let items: Vec<ListItem> = app
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            let width = column.width().map_or("fill".to_string(), |w| w.to_string());
+            let text = format!(
+                "[{}] {:<10} {:>4}",
+                if column.shown { "x" } else { " " },
+                column.kind.header(),
+                width
+            );
+            if i == app.column_cursor {
+                ListItem::new(text).white().bold().on_blue()
+            } else {
+                ListItem::new(text)
+            }
+        })
+        .collect();
+    let block = Block::bordered()
+        .title(" Columns ".bold())
+        .title_bottom(" <Space> <</> +/- <S>ave <Esc> ".dark_gray())
+        .border_style(ratatui::style::Style::default().yellow());
+    Clear.render(popup, buf);
+    Widget::render(List::new(items).block(block), popup, buf);
+}
 
 pub fn render_session_list(app: &App, area: Rect, buf: &mut Buffer) {
-    let visible_sessions = app.get_visible_sessions();
+    let rows = app.session_rows();
 
-    if visible_sessions.is_empty() {
-        let no_sessions_text = ratatui::text::Text::from(vec![ratatui::text::Line::from(vec![
-            "There are no sessions for this action".into(),
-        ])]);
+    if rows.is_empty() {
+        let message = if app.tag_filter.is_empty() {
+            "There are no sessions for this action".to_string()
+        } else {
+            format!(
+                "There are no sessions tagged {} (<F> to change)",
+                state::tag_line(&app.tag_filter)
+            )
+        };
+        let no_sessions_text =
+            ratatui::text::Text::from(vec![ratatui::text::Line::from(vec![message.into()])]);
 
         Paragraph::new(no_sessions_text)
             .centered()
             .block(Block::bordered().title(" Zellij Sessions ".bold()))
             .render(area, buf);
     } else {
-        let items: Vec<ListItem> = visible_sessions
-            .iter()
-            .enumerate()
-            .map(|(i, s)| {
-                let status = if s.exited { " (EXITED)" } else { "" };
-                let content = format!("{}. {} - {}s ago{}", i + 1, s.name, s.time, status);
-                if i == app.selected_session {
-                    ListItem::new(content).white().bold().on_blue()
-                } else {
-                    ListItem::new(content)
-                }
-            })
-            .collect();
-
         let session_title = match app.input_mode {
             InputMode::GoToIndex => format!(" Go to index: {}_ ", app.goto_buffer)
                 .yellow()
                 .bold(),
-            _ => " Zellij Sessions ".bold(),
+            _ => format!(
+                " Zellij Sessions (by {}{}{}) ",
+                app.sort_mode.name(),
+                if app.grouped { ", grouped" } else { "" },
+                if app.tag_filter.is_empty() {
+                    String::new()
+                } else {
+                    format!(", {}", state::tag_line(&app.tag_filter))
+                }
+            )
+            .bold(),
         };
 
-        let session_list = List::new(items)
-            .block(Block::bordered().title(session_title))
-            .highlight_symbol(">> ");
+        let chunks = Layout::default()
+            .direction(ratatui::layout::Direction::Horizontal)
+            .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+            .split(area);
 
-        Widget::render(session_list, area, buf);
+        let block = Block::bordered().title(session_title);
+        if app.action.title == "Sessions" {
+            render_session_table(app, &rows, block, chunks[0], buf);
+            if app.input_mode == InputMode::Columns {
+                render_column_picker(app, chunks[0], buf);
+            }
+        } else {
+            let now = SystemTime::now();
+            let items: Vec<ListItem> = rows
+                .iter()
+                .enumerate()
+                .map(|(i, row)| {
+                    let s = match row {
+                        SessionRow::Group {
+                            name,
+                            sessions,
+                            collapsed,
+                        } => {
+                            let label = group_label(name, sessions.len(), *collapsed);
+                            let item =
+                                ListItem::new(format!("{}. {}", i + 1, label).magenta().bold());
+                            return if i == app.selected_session {
+                                item.white().on_blue()
+                            } else {
+                                item
+                            };
+                        }
+                        SessionRow::Session(s) => s,
+                    };
+                    let indent = if app.grouped && app.group_by.key(&s.name).is_some() {
+                        "  "
+                    } else {
+                        ""
+                    };
+                    let status = if s.exited {
+                        " (EXITED)"
+                    } else if app.current_session.as_ref() == Some(&s.name) {
+                        " (CURRENT)"
+                    } else {
+                        ""
+                    };
+                    let when = session_when(app, s, now);
+                    let pin = pin_marker(app.pins.sessions.contains(&s.name));
+                    let tags = app
+                        .session_meta
+                        .get(&s.name)
+                        .filter(|m| !m.tags.is_empty())
+                        .map(|m| format!("  {}", m.tag_line()))
+                        .unwrap_or_default();
+                    let content = format!(
+                        "{}. {}{}{} - {}{}{}",
+                        i + 1,
+                        indent,
+                        pin,
+                        s.name,
+                        when,
+                        status,
+                        tags
+                    );
+                    if i == app.selected_session {
+                        ListItem::new(content).white().bold().on_blue()
+                    } else {
+                        ListItem::new(content)
+                    }
+                })
+                .collect();
+            let session_list = List::new(items).block(block).highlight_symbol(">> ");
+            Widget::render(session_list, chunks[0], buf);
+        }
+        if matches!(app.selected_row(), Some(SessionRow::Group { .. })) {
+            render_group_detail(app, chunks[1], buf);
+        } else if app.selected_session().is_some_and(|s| !s.exited) {
+            let side_chunks = Layout::default()
+                .direction(ratatui::layout::Direction::Vertical)
+                .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+                .split(chunks[1]);
+            render_session_detail(app, side_chunks[0], buf);
+            render_screen_preview(app, side_chunks[1], buf);
+        } else {
+            render_session_detail(app, chunks[1], buf);
+        }
     }
 }
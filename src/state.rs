@@ -0,0 +1,198 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::SortMode;
+
+const SORT_FILE: &str = "sort";
+const ATTACHED_FILE: &str = "attached";
+const PINS_FILE: &str = "pins";
+const META_FILE: &str = "meta";
+
+/// The sort mode saved in `dir`, or the default when there is none.
+pub fn load_sort_mode(dir: &Path) -> SortMode {
+    fs::read_to_string(dir.join(SORT_FILE))
+        .ok()
+        .and_then(|s| SortMode::from_name(s.trim()))
+        .unwrap_or_default()
+}
+
+pub fn save_sort_mode(dir: &Path, mode: SortMode) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    fs::write(dir.join(SORT_FILE), format!("{}\n", mode.name()))
+}
+
+/// When each session was last attached to from zellui. The file holds one
+/// `<unix seconds>\t<session name>` line per session.
+pub fn load_attach_history(dir: &Path) -> HashMap<String, SystemTime> {
+    let Ok(src) = fs::read_to_string(dir.join(ATTACHED_FILE)) else {
+        return HashMap::new();
+    };
+    src.lines()
+        .filter_map(|line| {
+            let (secs, name) = line.split_once('\t')?;
+            let secs = secs.parse::<u64>().ok()?;
+            Some((name.to_string(), UNIX_EPOCH + Duration::from_secs(secs)))
+        })
+        .collect()
+}
+
+/// Notes `name` as attached at `when`, returning the updated history.
+pub fn record_attach(
+    dir: &Path,
+    name: &str,
+    when: SystemTime,
+) -> io::Result<HashMap<String, SystemTime>> {
+    let mut history = load_attach_history(dir);
+    history.insert(name.to_string(), when);
+    save_attach_history(dir, &history)?;
+    Ok(history)
+}
+
+/// Drops deleted sessions from the history, returning what is left.
+pub fn forget_attached(dir: &Path, names: &[String]) -> io::Result<HashMap<String, SystemTime>> {
+    let mut history = load_attach_history(dir);
+    let before = history.len();
+    history.retain(|name, _| !names.contains(name));
+    if history.len() != before {
+        save_attach_history(dir, &history)?;
+    }
+    Ok(history)
+}
+
+fn save_attach_history(dir: &Path, history: &HashMap<String, SystemTime>) -> io::Result<()> {
+    let mut entries: Vec<(&String, &SystemTime)> = history.iter().collect();
+    entries.sort();
+    let contents: String = entries
+        .iter()
+        .map(|(name, when)| {
+            let secs = when
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default();
+            format!("{}\t{}\n", secs, name)
+        })
+        .collect();
+    fs::create_dir_all(dir)?;
+    fs::write(dir.join(ATTACHED_FILE), contents)
+}
+
+/// Sessions (by name) and layouts (by `KdlFile::layout_arg`) kept at the top
+/// of their lists.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Pins {
+    pub sessions: HashSet<String>,
+    pub layouts: HashSet<String>,
+}
+
+/// The pins saved in `dir`: one `session\t<name>` or `layout\t<path>` line
+/// each.
+pub fn load_pins(dir: &Path) -> Pins {
+    let mut pins = Pins::default();
+    let Ok(src) = fs::read_to_string(dir.join(PINS_FILE)) else {
+        return pins;
+    };
+    for line in src.lines() {
+        match line.split_once('\t') {
+            Some(("session", name)) => pins.sessions.insert(name.to_string()),
+            Some(("layout", key)) => pins.layouts.insert(key.to_string()),
+            _ => false,
+        };
+    }
+    pins
+}
+
+pub fn save_pins(dir: &Path, pins: &Pins) -> io::Result<()> {
+    let mut lines: Vec<String> = pins
+        .sessions
+        .iter()
+        .map(|name| format!("session\t{}", name))
+        .chain(pins.layouts.iter().map(|key| format!("layout\t{}", key)))
+        .collect();
+    lines.sort();
+    fs::create_dir_all(dir)?;
+    fs::write(
+        dir.join(PINS_FILE),
+        lines
+            .iter()
+            .map(|line| format!("{}\n", line))
+            .collect::<String>(),
+    )
+}
+
+/// Tags and a free-text note attached to a session.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionMeta {
+    pub tags: Vec<String>,
+    pub note: String,
+}
+
+impl SessionMeta {
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.note.is_empty()
+    }
+
+    /// The tags as typed, e.g. `#infra #demo`.
+    pub fn tag_line(&self) -> String {
+        tag_line(&self.tags)
+    }
+}
+
+/// Tags as typed, e.g. `#infra #demo`.
+pub fn tag_line(tags: &[String]) -> String {
+    tags.iter()
+        .map(|tag| format!("#{}", tag))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Tags from user input: separated by spaces or commas, with or without a
+/// leading `#`, without duplicates.
+pub fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in input.split([' ', ',', '\t']) {
+        let tag = tag.trim_start_matches('#');
+        if !tag.is_empty() && !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+/// The session metadata saved in `dir`. The file holds one
+/// `<session name>\t<space separated tags>\t<note>` line per session.
+pub fn load_session_meta(dir: &Path) -> HashMap<String, SessionMeta> {
+    let Ok(src) = fs::read_to_string(dir.join(META_FILE)) else {
+        return HashMap::new();
+    };
+    src.lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            let name = fields.next().filter(|name| !name.is_empty())?;
+            let meta = SessionMeta {
+                tags: parse_tags(fields.next().unwrap_or_default()),
+                note: fields.next().unwrap_or_default().to_string(),
+            };
+            Some((name.to_string(), meta))
+        })
+        .collect()
+}
+
+/// Writes `meta`, leaving out sessions with neither tags nor a note. Tabs and
+/// newlines in notes become spaces.
+pub fn save_session_meta(dir: &Path, meta: &HashMap<String, SessionMeta>) -> io::Result<()> {
+    let mut entries: Vec<(&String, &SessionMeta)> =
+        meta.iter().filter(|(_, m)| !m.is_empty()).collect();
+    entries.sort_by_key(|(name, _)| *name);
+    let contents: String = entries
+        .iter()
+        .map(|(name, m)| {
+            let note = m.note.replace(['\t', '\n', '\r'], " ");
+            format!("{}\t{}\t{}\n", name, m.tags.join(" "), note)
+        })
+        .collect();
+    fs::create_dir_all(dir)?;
+    fs::write(dir.join(META_FILE), contents)
+}
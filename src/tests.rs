@@ -4,43 +4,170 @@ use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::widgets::Widget;
 
+fn session(name: &str, exited: bool) -> Session {
+    Session {
+        name: name.to_string(),
+        created: None,
+        exited,
+    }
+}
+
 #[test]
 fn test_parse_time() {
-    assert_eq!(parse_time("1s"), 1);
-    assert_eq!(parse_time("1m"), 60);
-    assert_eq!(parse_time("1h"), 3600);
-    assert_eq!(parse_time("1day"), 86400);
-    assert_eq!(parse_time("2days"), 172800);
-    assert_eq!(parse_time("1day 2h 3m 4s"), 86400 + 7200 + 180 + 4);
-    assert_eq!(parse_time(""), 0);
+    let secs = |s: &str| parse_time(s).map(|d| d.as_secs());
+    assert_eq!(secs("1s"), Ok(1));
+    assert_eq!(secs("1m"), Ok(60));
+    assert_eq!(secs("1h"), Ok(3600));
+    assert_eq!(secs("1day"), Ok(86400));
+    assert_eq!(secs("2days"), Ok(172800));
+    assert_eq!(secs("1day 2h 3m 4s"), Ok(86400 + 7200 + 180 + 4));
+    assert_eq!(secs("3weeks"), Ok(3 * 7 * 86400));
+    assert_eq!(secs("1month 2days"), Ok(2_630_016 + 172800));
+    assert_eq!(secs("2years"), Ok(2 * 31_557_600));
+    assert_eq!(secs("5s 120ms"), Ok(5));
+    assert!(parse_time("").is_err());
+    assert!(parse_time("3fortnights").is_err());
+    assert!(parse_time("days").is_err());
+    assert!(parse_time("99999999999999999999years").is_err());
+}
+
+/// Renders seconds the way zellij (via humantime) prints ages.
+fn humantime_style(mut secs: u64) -> String {
+    if secs == 0 {
+        return "0s".to_string();
+    }
+    let units = [
+        (31_557_600, "year", true),
+        (2_630_016, "month", true),
+        (86400, "day", true),
+        (3600, "h", false),
+        (60, "m", false),
+        (1, "s", false),
+    ];
+    let mut parts = Vec::new();
+    for (size, unit, plural) in units {
+        let n = secs / size;
+        secs %= size;
+        if n > 0 {
+            let s = if plural && n > 1 { "s" } else { "" };
+            parts.push(format!("{}{}{}", n, unit, s));
+        }
+    }
+    parts.join(" ")
+}
+
+#[test]
+fn test_parse_time_properties() {
+    // A fixed LCG stands in for a property-testing crate
+    let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = move || {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        seed >> 33
+    };
+    for _ in 0..2000 {
+        let secs = match next() % 4 {
+            0 => next() % 60,
+            1 => next() % 86400,
+            2 => next() % (90 * 86400),
+            _ => next() % (40 * 31_557_600),
+        };
+        let text = humantime_style(secs);
+        // Round trip: whatever zellij prints parses back to the same age
+        assert_eq!(parse_time(&text).map(|d| d.as_secs()), Ok(secs), "{}", text);
+        // Token order doesn't matter and each extra token only adds
+        let reversed: Vec<&str> = text.split_whitespace().rev().collect();
+        assert_eq!(parse_time(&reversed.join(" ")), parse_time(&text));
+        let longer = format!("{} 1s", text);
+        assert_eq!(
+            parse_time(&longer).map(|d| d.as_secs()),
+            Ok(secs + 1),
+            "{}",
+            longer
+        );
+        // A junk token anywhere makes the whole age unknown
+        assert!(parse_time(&format!("{} 3parsecs", text)).is_err());
+    }
+}
+
+#[test]
+fn test_format_age() {
+    assert_eq!(helpers::format_age(0), "0s");
+    assert_eq!(helpers::format_age(59), "59s");
+    assert_eq!(helpers::format_age(3600), "1h");
+    assert_eq!(helpers::format_age(273600), "3d 4h");
+    assert_eq!(helpers::format_age(86400 + 59), "1d");
+}
+
+#[test]
+fn test_layout_meta() {
+    let src = r#"// Two tabs for hacking on the API
+// plus a log tail.
+layout {
+    default_tab_template {
+        pane size=1 borderless=true {
+            plugin location="zellij:tab-bar"
+        }
+        children
+    }
+    tab name="code" focus=true {
+        pane split_direction="vertical" {
+            pane command="nvim" { args "."; }
+            pane
+        }
+    }
+    tab name="logs" {
+        pane command="tail" cwd="/var/log" { args "-f" "syslog"; }
+        /-pane command="htop"
+    }
+    swap_tiled_layout name="stacked" {
+        tab {
+            pane stacked=true { pane; pane; }
+        }
+    }
+}
+"#;
+    let meta = kdl::layout_meta(src);
+    assert_eq!(
+        meta.description.as_deref(),
+        Some("Two tabs for hacking on the API plus a log tail.")
+    );
+    // The split container and the template and swap layout panes don't count
+    assert_eq!(meta.tabs, 2);
+    assert_eq!(meta.panes, 3);
+    assert_eq!(meta.commands, vec!["nvim", "tail"]);
+    assert_eq!(meta.plugins, vec!["zellij:tab-bar"]);
+
+    let nodes = kdl::parse_nodes(src);
+    let tail = nodes
+        .iter()
+        .find(|n| n.prop("command") == Some("tail"))
+        .unwrap();
+    assert_eq!(tail.depth, 2);
+    assert_eq!(tail.prop("cwd"), Some("/var/log"));
+    let args = nodes
+        .iter()
+        .find(|n| n.name == "args" && n.depth == 3 && n.args.len() == 2);
+    assert_eq!(args.unwrap().args, vec!["-f", "syslog"]);
 }
 
 #[test]
 fn test_session_navigation() {
     let mut app = App {
         action: ACTIONS[0], // Attach
-        sessions: vec![
-            Session {
-                name: "s1".to_string(),
-                time: 0,
-                exited: true,
-            },
-            Session {
-                name: "s2".to_string(),
-                time: 0,
-                exited: true,
-            },
-        ],
+        sessions: vec![session("s1", true), session("s2", true)],
         layouts: Vec::new(),
         configs: Vec::new(),
         selected_session: 0,
-        selected_layout: 0,
-        selected_config: 0,
+        selected_layout: None,
+        selected_config: None,
         focused_list: ListType::Session, // Changed to Session
         input_mode: InputMode::Normal,
         goto_buffer: String::new(),
         status_message: String::new(),
         exit: false,
+        ..App::default()
     };
 
     app.next_item();
@@ -61,56 +188,67 @@ fn test_file_navigation() {
             KdlFile {
                 name: "l1.kdl".to_string(),
                 path: PathBuf::from("l1.kdl"),
+                ..KdlFile::default()
             },
             KdlFile {
                 name: "l2.kdl".to_string(),
                 path: PathBuf::from("l2.kdl"),
+                ..KdlFile::default()
             },
         ],
         configs: vec![KdlFile {
             name: "c1.kdl".to_string(),
             path: PathBuf::from("c1.kdl"),
+            ..KdlFile::default()
         }],
         selected_session: 0,
-        selected_layout: 0,
-        selected_config: 0,
+        selected_layout: None,
+        selected_config: None,
         focused_list: ListType::Layout,
         input_mode: InputMode::Normal,
         goto_buffer: String::new(),
         status_message: String::new(),
         exit: false,
+        ..App::default()
     };
 
     app.next_item();
-    assert_eq!(app.selected_layout, 1);
+    assert_eq!(app.selected_layout, Some(0));
+    app.next_item();
+    assert_eq!(app.selected_layout, Some(1));
+    app.next_item();
+    assert_eq!(app.selected_layout, None); // Wrap around to "(none)"
+    app.previous_item();
+    assert_eq!(app.selected_layout, Some(1));
 
     // Switch focus
     app.focused_list = ListType::Config;
     app.next_item();
-    assert_eq!(app.selected_config, 0); // Only one item
+    assert_eq!(app.selected_config, Some(0)); // Only one item
+    app.next_item();
+    assert_eq!(app.selected_config, None);
+
+    // Nothing selected means no -l / -c at all
+    app.selected_layout = None;
+    assert_eq!(app.trigger_action(), Some(Vec::new()));
 }
 
 #[test]
 fn test_goto_index_session() {
     let mut app = App {
         action: ACTIONS[0],
-        sessions: (0..20)
-            .map(|i| Session {
-                name: format!("s{}", i),
-                time: 0,
-                exited: true,
-            })
-            .collect(),
+        sessions: (0..20).map(|i| session(&format!("s{}", i), true)).collect(),
         layouts: Vec::new(),
         configs: Vec::new(),
         selected_session: 0,
-        selected_layout: 0,
-        selected_config: 0,
+        selected_layout: None,
+        selected_config: None,
         focused_list: ListType::Session, // Changed to Session
         input_mode: InputMode::Normal,
         goto_buffer: String::new(),
         status_message: String::new(),
         exit: false,
+        ..App::default()
     };
 
     app.handle_key_event(KeyEvent::new(KeyCode::Char('g'), event::KeyModifiers::NONE));
@@ -122,12 +260,10 @@ fn test_goto_index_session() {
 
 #[test]
 fn test_render_no_panic() {
-    let mut app = App::default();
-    app.sessions = vec![Session {
-        name: "test".to_string(),
-        time: 0,
-        exited: true,
-    }];
+    let app = App {
+        sessions: vec![session("test", true)],
+        ..App::default()
+    };
     let mut buf = Buffer::empty(Rect::new(0, 0, 173, 50));
     app.render(buf.area, &mut buf);
 
@@ -138,16 +274,1358 @@ fn test_render_no_panic() {
 
 #[test]
 fn test_clamp_selection_empty() {
-    let mut app = App::default();
-    app.sessions = vec![Session {
-        name: "s1".to_string(),
-        time: 0,
-        exited: true,
-    }];
-    app.selected_session = 0;
+    let mut app = App {
+        sessions: vec![session("s1", true)],
+        selected_session: 0,
+        ..App::default()
+    };
 
     // Simulate deletion
     app.sessions.clear();
     app.clamp_selection();
     assert_eq!(app.selected_session, 0);
 }
+
+#[test]
+fn test_new_session_builtin_layout_arg() {
+    let mut app = App {
+        action: ACTIONS[3],
+        sessions: Vec::new(),
+        layouts: vec![
+            KdlFile::from_path("dev.kdl".to_string(), PathBuf::from("/tmp/layouts/dev.kdl")),
+            KdlFile::builtin("compact", "Single compact status bar"),
+        ],
+        configs: Vec::new(),
+        selected_session: 0,
+        selected_layout: Some(1),
+        selected_config: None,
+        focused_list: ListType::Layout,
+        input_mode: InputMode::Normal,
+        goto_buffer: String::new(),
+        status_message: String::new(),
+        exit: false,
+        ..App::default()
+    };
+
+    assert_eq!(
+        app.trigger_action(),
+        Some(vec!["-l".to_string(), "compact".to_string()])
+    );
+
+    app.selected_layout = Some(0);
+    assert_eq!(
+        app.trigger_action(),
+        Some(vec!["-l".to_string(), "/tmp/layouts/dev.kdl".to_string()])
+    );
+}
+
+#[test]
+fn test_preselect_config_default_layout() {
+    let layout = |name: &str| KdlFile {
+        name: name.to_string(),
+        path: PathBuf::from(format!("/layouts/{}", name)),
+        ..KdlFile::default()
+    };
+    let config = |name: &str, default_layout: Option<&str>| KdlFile {
+        name: name.to_string(),
+        path: PathBuf::from(format!("/config/{}", name)),
+        config: ConfigMeta {
+            default_layout: default_layout.map(str::to_string),
+            ..ConfigMeta::default()
+        },
+        ..KdlFile::default()
+    };
+    let mut app = App {
+        action: ACTIONS[3],
+        sessions: Vec::new(),
+        layouts: vec![
+            layout("default.kdl"),
+            layout("dev.kdl"),
+            KdlFile::builtin("compact", "Single compact status bar"),
+        ],
+        configs: vec![
+            config("config.kdl", Some("dev")),
+            config("plain.kdl", None),
+            config("small.kdl", Some("compact")),
+            config("pathed.kdl", Some("/layouts/dev.kdl")),
+            config("elsewhere.kdl", Some("/elsewhere/compact.kdl")),
+        ],
+        selected_session: 0,
+        selected_layout: None,
+        selected_config: None,
+        focused_list: ListType::Config,
+        input_mode: InputMode::Normal,
+        goto_buffer: String::new(),
+        status_message: String::new(),
+        exit: false,
+        ..App::default()
+    };
+
+    // No config picked: zellij reads config.kdl
+    app.preselect_layout();
+    assert_eq!(app.selected_layout, Some(1)); // dev
+    app.next_item();
+    assert_eq!(app.selected_config, Some(0));
+    assert_eq!(app.selected_layout, Some(1)); // dev
+    app.next_item();
+    assert_eq!(app.selected_layout, Some(0)); // no default_layout: default.kdl
+    app.next_item();
+    assert_eq!(app.selected_layout, Some(2)); // compact, by name
+    app.next_item();
+    assert_eq!(app.selected_layout, Some(1)); // dev, by path
+    app.next_item();
+    assert_eq!(app.selected_layout, None); // not the built-in compact
+
+    let meta = kdl::config_meta(
+        "// comment\ndefault_layout \"dev\"\ndefault_mode \"locked\"\nthemes {\n    theme \"nested\"\n}\ntheme \"dracula\"\n",
+    );
+    assert_eq!(meta.default_layout.as_deref(), Some("dev"));
+    assert_eq!(meta.default_mode.as_deref(), Some("locked"));
+    assert_eq!(meta.theme.as_deref(), Some("dracula"));
+}
+
+#[test]
+fn test_request_edit() {
+    let mut app = App {
+        action: ACTIONS[3],
+        layouts: vec![
+            KdlFile {
+                name: "dev.kdl".to_string(),
+                path: PathBuf::from("/layouts/dev.kdl"),
+                ..KdlFile::default()
+            },
+            KdlFile::builtin("compact", "Single compact status bar"),
+        ],
+        configs: Vec::new(),
+        selected_layout: Some(1),
+        selected_config: None,
+        focused_list: ListType::Layout,
+        input_mode: InputMode::Normal,
+        ..App::default()
+    };
+
+    app.handle_key_event(KeyEvent::new(KeyCode::Char('e'), event::KeyModifiers::NONE));
+    assert_eq!(app.pending_edit, None);
+    assert!(matches!(app.input_mode, InputMode::Status(_)));
+
+    app.input_mode = InputMode::Normal;
+    app.selected_layout = Some(0);
+    app.handle_key_event(KeyEvent::new(KeyCode::Char('e'), event::KeyModifiers::NONE));
+    assert_eq!(app.pending_edit, Some(PathBuf::from("/layouts/dev.kdl")));
+
+    assert_eq!(kdl::validate("layout {\n    pane\n}\n"), Ok(()));
+    assert!(kdl::validate("layout {\n    pane\n").is_err());
+    assert!(kdl::validate("layout {\n}\n}\n").is_err());
+    assert!(kdl::validate("layout {\n    pane command=\"htop\n}\n").is_err());
+    assert_eq!(kdl::validate("// a { in a comment\nlayout \"{\"\n"), Ok(()));
+}
+
+#[test]
+fn test_layout_file_operations() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let layout_dir = root.join("layouts");
+    let trash_dir = root.join("trash");
+    std::fs::create_dir_all(&layout_dir).unwrap();
+    let dev = layout_dir.join("dev.kdl");
+    std::fs::write(&dev, "layout {\n    pane\n}\n").unwrap();
+
+    assert_eq!(
+        layout_files::layout_file_name(" api "),
+        Ok("api.kdl".to_string())
+    );
+    assert_eq!(
+        layout_files::layout_file_name("api.kdl"),
+        Ok("api.kdl".to_string())
+    );
+    assert!(layout_files::layout_file_name("").is_err());
+    assert!(layout_files::layout_file_name("../escape").is_err());
+
+    // Duplicate via the prompt, as the user would
+    let mut app = App {
+        action: ACTIONS[3],
+        layouts: vec![KdlFile::from_path("dev.kdl".to_string(), dev.clone())],
+        selected_layout: Some(0),
+        focused_list: ListType::Layout,
+        input_mode: InputMode::Normal,
+        ..App::default()
+    };
+    app.handle_key_event(KeyEvent::new(KeyCode::Char('c'), event::KeyModifiers::NONE));
+    assert_eq!(app.input_mode, InputMode::Input(Prompt::DuplicateLayout));
+    assert_eq!(app.input_buffer, "dev-copy");
+    app.handle_key_event(KeyEvent::new(KeyCode::Enter, event::KeyModifiers::NONE));
+    let copy = layout_dir.join("dev-copy.kdl");
+    assert!(copy.is_file());
+    assert!(dev.is_file());
+
+    // Refuses to clobber
+    assert!(layout_files::duplicate(&dev, "dev-copy.kdl").is_err());
+
+    // Renaming keeps a pinned layout pinned under its new path
+    app.layouts = vec![KdlFile::from_path("dev-copy.kdl".to_string(), copy.clone())];
+    app.selected_layout = Some(0);
+    app.input_mode = InputMode::Normal;
+    app.pins.layouts = HashSet::from([copy.to_string_lossy().to_string()]);
+    app.handle_key_event(KeyEvent::new(KeyCode::Char('M'), event::KeyModifiers::NONE));
+    assert_eq!(app.input_mode, InputMode::Input(Prompt::RenameLayout));
+    app.input_buffer = "api".to_string();
+    app.handle_key_event(KeyEvent::new(KeyCode::Enter, event::KeyModifiers::NONE));
+    let renamed = layout_dir.join("api.kdl");
+    assert!(!copy.exists());
+    assert!(renamed.is_file());
+    assert_eq!(
+        app.pins.layouts,
+        HashSet::from([renamed.to_string_lossy().to_string()])
+    );
+
+    layout_files::trash(&renamed, &trash_dir).unwrap();
+    assert!(!renamed.exists());
+    let restored = layout_files::restore_latest(&trash_dir, &layout_dir).unwrap();
+    assert_eq!(restored, Some(renamed.clone()));
+    assert!(renamed.is_file());
+    assert_eq!(
+        layout_files::restore_latest(&trash_dir, &layout_dir).unwrap(),
+        None
+    );
+
+    let created =
+        layout_files::create_from_template(&layout_dir, "new.kdl", "compact", "layout {}\n")
+            .unwrap();
+    let meta = kdl::layout_meta(&std::fs::read_to_string(created).unwrap());
+    assert_eq!(
+        meta.description.as_deref(),
+        Some("Based on zellij's built-in \"compact\" layout")
+    );
+}
+
+#[test]
+fn test_layout_tabs_from_dump() {
+    let dump = r#"layout {
+    cwd "/home/me"
+    tab name="api" focus=true hide_floating_panes=true {
+        pane size=1 borderless=true {
+            plugin location="zellij:tab-bar"
+        }
+        pane split_direction="vertical" {
+            pane command="nvim" cwd="src/api" focus=true {
+                args "main.rs"
+                start_suspended true
+            }
+            pane cwd="src/api"
+        }
+        pane size=2 borderless=true {
+            plugin location="zellij:status-bar"
+        }
+    }
+    tab name="logs" cwd="/var/log" {
+        pane command="tail" {
+            args "-f" "syslog"
+        }
+        floating_panes {
+            pane plugin="file:/plugins/monitor.wasm"
+        }
+    }
+    new_tab_template {
+        pane command="should-not-appear"
+    }
+    swap_tiled_layout name="vertical" {
+        tab max_panes=5 {
+            pane
+        }
+    }
+}
+"#;
+    let tabs = kdl::layout_tabs(dump);
+    assert_eq!(tabs.len(), 2);
+
+    assert_eq!(tabs[0].name, "api");
+    assert!(tabs[0].focused);
+    assert_eq!(
+        tabs[0].panes,
+        vec![
+            PaneInfo {
+                command: Some("nvim main.rs".to_string()),
+                cwd: Some("/home/me/src/api".to_string()),
+                plugin: None,
+                focused: true,
+            },
+            PaneInfo {
+                command: None,
+                cwd: Some("/home/me/src/api".to_string()),
+                plugin: None,
+                focused: false,
+            },
+        ]
+    );
+
+    assert_eq!(tabs[1].name, "logs");
+    assert!(!tabs[1].focused);
+    assert_eq!(tabs[1].panes.len(), 2);
+    assert_eq!(tabs[1].panes[0].command.as_deref(), Some("tail -f syslog"));
+    assert_eq!(tabs[1].panes[0].cwd.as_deref(), Some("/var/log"));
+    assert_eq!(
+        tabs[1].panes[1].plugin.as_deref(),
+        Some("file:/plugins/monitor.wasm")
+    );
+
+    // Cached details are rendered for the selected live session
+    let app = App {
+        action: ACTIONS[1], // Kill: live sessions
+        sessions: vec![session("work", false)],
+        session_details: HashMap::from([(
+            "work".to_string(),
+            SessionDetail {
+                tabs,
+                ..SessionDetail::default()
+            },
+        )]),
+        selected_session: 0,
+        ..App::default()
+    };
+    let mut buf = Buffer::empty(Rect::new(0, 0, 120, 30));
+    app.render(buf.area, &mut buf);
+    let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+    assert!(text.contains("nvim main.rs"));
+    assert!(text.contains("tail -f syslog"));
+}
+
+#[test]
+fn test_screen_preview_text() {
+    assert_eq!(
+        helpers::strip_ansi("\u{1b}[1;32mok\u{1b}[0m done\u{1b}]0;title\u{7}!"),
+        "ok done!"
+    );
+    assert_eq!(helpers::strip_ansi("a\tb\r"), "a    b");
+
+    let screen = "one\ntwo\n\u{1b}[31mthree\u{1b}[0m\n\n   \n";
+    assert_eq!(helpers::last_lines(screen, 2), vec!["two", "three"]);
+    assert_eq!(helpers::last_lines(screen, 10), vec!["one", "two", "three"]);
+    assert!(helpers::last_lines("\n\n", 10).is_empty());
+
+    // Selecting a live session schedules a preview instead of dumping at once
+    let mut app = App {
+        action: ACTIONS[1],
+        sessions: vec![session("work", false), session("play", false)],
+        selected_session: 0,
+        preview: None,
+        preview_due: None,
+        ..App::default()
+    };
+    app.update_preview();
+    assert!(app.preview.is_none());
+    let (pending, first_due) = app.preview_due.clone().unwrap();
+    assert_eq!(pending, "work");
+
+    // Moving on restarts the wait for the newly highlighted session
+    std::thread::sleep(Duration::from_millis(5));
+    app.selected_session = 1;
+    app.update_preview();
+    let (pending, due) = app.preview_due.clone().unwrap();
+    assert_eq!(pending, "play");
+    assert!(due > first_due);
+    assert!(app.preview.is_none());
+
+    // Once due, the screen is dumped by the loader rather than on the UI thread
+    app.preview_due = Some(("play".to_string(), Instant::now()));
+    app.update_preview();
+    assert!(app.preview_due.is_none());
+    assert_eq!(app.detail_loader.screen_pending(), Some("play"));
+    app.update_preview();
+    assert!(app.preview_due.is_none());
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while app.preview.is_none() && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(5));
+        app.receive_loaded();
+    }
+    assert_eq!(app.preview.as_ref().unwrap().session, "play");
+    assert_eq!(app.detail_loader.screen_pending(), None);
+
+    // Moving to a view without live sessions cancels it
+    app.action = ACTIONS[2];
+    app.update_preview();
+    assert!(app.preview_due.is_none());
+}
+
+#[test]
+fn test_resurrection_layout_path() {
+    let tmp = tempfile::tempdir().unwrap();
+    let cache = tmp.path();
+    let write = |version: &str, contents: &str| {
+        let dir = cache.join(version).join("session_info").join("old-work");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("session-layout.kdl");
+        std::fs::write(&path, contents).unwrap();
+        path
+    };
+    write(
+        "0.40.1",
+        "layout {\n    tab name=\"stale\" {\n        pane\n    }\n}\n",
+    );
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    let newest = write(
+        "contract_version_1",
+        "layout {\n    cwd \"/srv\"\n    tab name=\"db\" {\n        pane command=\"psql\"\n    }\n}\n",
+    );
+
+    assert_eq!(
+        zellij::resurrection_layout_path(cache, "old-work"),
+        Some(newest.clone())
+    );
+    assert_eq!(zellij::resurrection_layout_path(cache, "missing"), None);
+
+    let tabs = kdl::layout_tabs(&std::fs::read_to_string(newest).unwrap());
+    assert_eq!(tabs[0].name, "db");
+    assert_eq!(tabs[0].panes[0].command.as_deref(), Some("psql"));
+    assert_eq!(tabs[0].panes[0].cwd.as_deref(), Some("/srv"));
+}
+
+#[test]
+fn test_tab_drill_in() {
+    let tab = |name: &str, focused: bool| TabInfo {
+        name: name.to_string(),
+        panes: Vec::new(),
+        focused,
+    };
+    let mut app = App {
+        action: ACTIONS[0], // Attach
+        sessions: vec![session("old", true), session("work", false)],
+        session_details: HashMap::from([(
+            "work".to_string(),
+            SessionDetail {
+                tabs: vec![tab("code", false), tab("logs", true), tab("db", false)],
+                ..SessionDetail::default()
+            },
+        )]),
+        selected_session: 0,
+        focused_list: ListType::Session,
+        input_mode: InputMode::Normal,
+        ..App::default()
+    };
+    let press = |app: &mut App, code: KeyCode| {
+        app.handle_key_event(KeyEvent::new(code, event::KeyModifiers::NONE))
+    };
+
+    // Exited sessions have no live tabs to pick from
+    press(&mut app, KeyCode::Right);
+    assert_eq!(app.focused_list, ListType::Session);
+    app.input_mode = InputMode::Normal;
+
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Right);
+    assert_eq!(app.focused_list, ListType::Tab);
+    assert_eq!(app.selected_tab, 1); // starts on the session's focused tab
+
+    press(&mut app, KeyCode::Down);
+    assert_eq!(app.selected_tab, 2);
+    assert_eq!(app.selected_session, 1);
+    assert_eq!(app.selected_tab_info().map(|t| t.name.as_str()), Some("db"));
+    press(&mut app, KeyCode::Down);
+    assert_eq!(app.selected_tab, 0);
+
+    press(&mut app, KeyCode::Esc);
+    assert_eq!(app.focused_list, ListType::Session);
+    assert_eq!(app.selected_session, 1);
+
+    // Attaching from a tab leaves it for the helper zellui starts with zellij
+    press(&mut app, KeyCode::Right);
+    app.current_session = None;
+    assert_eq!(
+        press(&mut app, KeyCode::Enter),
+        Some(vec!["attach".to_string(), "work".to_string()])
+    );
+    let (session, tab, _) = app.pending_tab_focus.clone().unwrap();
+    assert_eq!((session.as_str(), tab.as_str()), ("work", "logs"));
+
+    // Other views have no tab to attach to, so Enter must not reach the kill
+    app.action = ACTIONS[1]; // Kill
+    app.focused_list = ListType::Session;
+    app.input_mode = InputMode::Normal;
+    app.select_session_by_name("work");
+    press(&mut app, KeyCode::Right);
+    assert_eq!(app.focused_list, ListType::Session);
+    assert_eq!(press(&mut app, KeyCode::Enter), None);
+    assert_eq!(app.status_message, "Tabs can only be picked to attach to");
+    assert_eq!(app.sessions.len(), 2);
+}
+
+#[test]
+fn test_strip_session_bits() {
+    let serialized = r#"layout {
+    cwd "/home/me/src/api"
+    tab name="code" focus=true {
+        pane command="cargo" cwd="crates/server" contents_file="/tmp/zellij/pane-3.txt" {
+            args "watch"
+            start_suspended true
+        }
+        pane cwd="docs"
+    }
+    new_tab_template {
+        pane cwd="/home/me"
+    }
+}
+"#;
+    let stripped = kdl::strip_session_bits(serialized);
+    assert_eq!(
+        stripped,
+        r#"layout {
+    tab name="code" focus=true {
+        pane command="cargo" cwd="crates/server" {
+            args "watch"
+        }
+        pane cwd="docs"
+    }
+    new_tab_template {
+        pane cwd="/home/me"
+    }
+}
+"#
+    );
+    assert_eq!(kdl::validate(&stripped), Ok(()));
+
+    let tmp = tempfile::tempdir().unwrap();
+    let dir = tmp.path();
+    let path = layout_files::save_session_layout(dir, "api.kdl", "api", serialized).unwrap();
+    let saved = KdlFile::from_path("api.kdl".to_string(), path);
+    assert_eq!(
+        saved.meta.description.as_deref(),
+        Some("Saved from session \"api\"")
+    );
+    assert_eq!(saved.meta.commands, vec!["cargo"]);
+}
+
+#[test]
+fn test_clone_session_args() {
+    let mut app = App {
+        action: ACTIONS[0],
+        sessions: vec![session("api", true), session("api-clone", true)],
+        layouts: vec![KdlFile::builtin("compact", "Single compact status bar")],
+        configs: vec![KdlFile {
+            name: "work.kdl".to_string(),
+            path: PathBuf::from("/config/work.kdl"),
+            ..KdlFile::default()
+        }],
+        selected_session: 0,
+        selected_layout: Some(0),
+        selected_config: Some(0),
+        focused_list: ListType::Session,
+        input_mode: InputMode::Normal,
+        ..App::default()
+    };
+
+    assert_eq!(
+        app.new_session_args(),
+        vec!["-l", "compact", "-c", "/config/work.kdl"]
+    );
+
+    // Clone layouts keep the original's directories but lose its scrollback
+    // and suspended commands; old ones are cleared
+    let tmp = tempfile::tempdir().unwrap();
+    let dir = tmp.path();
+    let old = dir.join("old.kdl");
+    fs::write(&old, "layout {}").unwrap();
+    fs::File::options()
+        .write(true)
+        .open(&old)
+        .unwrap()
+        .set_modified(SystemTime::now() - Duration::from_secs(2 * 24 * 60 * 60))
+        .unwrap();
+    let path = layout_files::write_clone_layout(
+        dir,
+        "api/2",
+        "layout {\n    cwd \"/srv\"\n    tab {\n        pane command=\"make\" {\n            start_suspended true\n        }\n    }\n}\n",
+    )
+    .unwrap();
+    assert_eq!(path, dir.join("api_2.kdl"));
+    let written = fs::read_to_string(&path).unwrap();
+    assert!(!written.contains("start_suspended"));
+    assert!(written.contains("cwd \"/srv\""));
+    assert!(!old.exists());
+
+    app.handle_key_event(KeyEvent::new(KeyCode::Char('c'), event::KeyModifiers::NONE));
+    assert_eq!(app.input_mode, InputMode::Input(Prompt::CloneSession));
+    assert_eq!(app.input_buffer, "api-clone");
+
+    // Names of existing sessions are refused before touching zellij
+    let result = app.handle_key_event(KeyEvent::new(KeyCode::Enter, event::KeyModifiers::NONE));
+    assert_eq!(result, None);
+    assert!(!app.exit);
+    assert_eq!(
+        app.input_mode,
+        InputMode::Status("A session named api-clone already exists".to_string())
+    );
+}
+
+#[test]
+fn test_inside_zellij_switches_session() {
+    let mut app = App {
+        action: ACTIONS[0],
+        sessions: vec![session("here", false), session("there", false)],
+        current_session: Some("here".to_string()),
+        selected_session: 0,
+        focused_list: ListType::Session,
+        input_mode: InputMode::Normal,
+        ..App::default()
+    };
+
+    assert_eq!(app.trigger_action(), None);
+    assert!(!app.exit);
+
+    app.selected_session = 1;
+    assert_eq!(
+        app.trigger_action(),
+        Some(vec![
+            "action".to_string(),
+            "switch-session".to_string(),
+            "there".to_string()
+        ])
+    );
+
+    // The current session can't be killed from its own pane
+    app.action = ACTIONS[1];
+    let killable: Vec<&str> = app
+        .get_visible_sessions()
+        .iter()
+        .map(|s| s.name.as_str())
+        .collect();
+    assert_eq!(killable, vec!["there"]);
+
+    // New sessions are started in the background, under a free name when
+    // they have none, rather than nested in zellui's pane
+    app.sessions.push(session("dev", false));
+    assert_eq!(app.free_session_name(Some("/layouts/dev.kdl")), "dev-2");
+    assert_eq!(app.free_session_name(Some("compact")), "compact");
+    assert_eq!(app.free_session_name(None), "session");
+
+    // With --print, nothing may be created before the shell runs the command
+    app.print_only = true;
+    app.exit = false;
+    assert_eq!(app.start_session(None, None, None), None);
+    assert!(!app.exit);
+    assert!(app.status_message.contains("--print"));
+    app.print_only = false;
+
+    // Outside zellij, attach and start sessions as before
+    app.current_session = None;
+    app.action = ACTIONS[0];
+    app.exit = false;
+    assert_eq!(
+        app.trigger_action(),
+        Some(vec!["attach".to_string(), "there".to_string()])
+    );
+    assert_eq!(
+        app.start_session(Some("new".to_string()), Some("compact".to_string()), None),
+        Some(vec![
+            "-s".to_string(),
+            "new".to_string(),
+            "-l".to_string(),
+            "compact".to_string()
+        ])
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_exit_code_propagation() {
+    use std::os::unix::process::ExitStatusExt;
+    assert_eq!(helpers::exit_code(std::process::ExitStatus::from_raw(0)), 0);
+    assert_eq!(
+        helpers::exit_code(std::process::ExitStatus::from_raw(3 << 8)),
+        3
+    );
+    // Killed by SIGKILL
+    assert_eq!(
+        helpers::exit_code(std::process::ExitStatus::from_raw(9)),
+        137
+    );
+}
+
+#[test]
+fn test_launcher_loop_args() {
+    let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+    assert_eq!(Cli::parse(args(&[])).unwrap(), Some(Cli::default()));
+    assert_eq!(
+        Cli::parse(args(&["--loop"])).unwrap(),
+        Some(Cli {
+            launcher: true,
+            ..Cli::default()
+        })
+    );
+    assert_eq!(Cli::parse(args(&["-h"])).unwrap(), None);
+    assert!(Cli::parse(args(&["--bogus"])).is_err());
+
+    assert_eq!(
+        App::session_from_args(&args(&["attach", "api"])),
+        Some("api".to_string())
+    );
+    assert_eq!(
+        App::session_from_args(&args(&["action", "switch-session", "db"])),
+        Some("db".to_string())
+    );
+    assert_eq!(
+        App::session_from_args(&args(&["-s", "api-2", "-l", "/tmp/x.kdl"])),
+        Some("api-2".to_string())
+    );
+    assert_eq!(App::session_from_args(&args(&["-l", "compact"])), None);
+
+    let mut app = App {
+        action: ACTIONS[0],
+        sessions: (0..5).map(|i| session(&format!("s{}", i), false)).collect(),
+        selected_session: 0,
+        ..App::default()
+    };
+    app.select_session_by_name("s3");
+    assert_eq!(app.selected_session, 3);
+    app.select_session_by_name("missing");
+    assert_eq!(app.selected_session, 3);
+}
+
+#[test]
+fn test_print_mode() {
+    let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+    assert_eq!(
+        Cli::parse(args(&["-p"])).unwrap().unwrap().print,
+        Some(PrintMode::Command)
+    );
+    assert_eq!(
+        Cli::parse(args(&["--print-session"]))
+            .unwrap()
+            .unwrap()
+            .print,
+        Some(PrintMode::Session)
+    );
+    assert!(Cli::parse(args(&["--print", "--loop"])).is_err());
+
+    assert_eq!(helpers::shell_quote("api-2"), "api-2");
+    assert_eq!(helpers::shell_quote(""), "''");
+    assert_eq!(helpers::shell_quote("my session"), "'my session'");
+    assert_eq!(helpers::shell_quote("it's"), "'it'\\''s'");
+    assert_eq!(
+        helpers::shell_command(&args(&["-s", "web app", "-l", "/tmp/x.kdl"])),
+        "zellij -s 'web app' -l /tmp/x.kdl"
+    );
+}
+
+#[test]
+fn test_yank() {
+    assert_eq!(helpers::base64(b""), "");
+    assert_eq!(helpers::base64(b"f"), "Zg==");
+    assert_eq!(helpers::base64(b"fo"), "Zm8=");
+    assert_eq!(helpers::base64(b"foobar"), "Zm9vYmFy");
+    assert_eq!(helpers::osc52("hi"), "\x1b]52;c;aGk=\x07");
+
+    let mut app = App {
+        action: ACTIONS[0],
+        sessions: vec![session("web app", false)],
+        layouts: vec![KdlFile::builtin("compact", "Single compact status bar")],
+        selected_session: 0,
+        selected_layout: Some(0),
+        focused_list: ListType::Session,
+        input_mode: InputMode::Normal,
+        ..App::default()
+    };
+
+    app.handle_key_event(KeyEvent::from(KeyCode::Char('y')));
+    assert_eq!(app.pending_yank.as_deref(), Some("zellij attach 'web app'"));
+    assert!(matches!(app.input_mode, InputMode::Status(_)));
+
+    app.input_mode = InputMode::Normal;
+    app.handle_key_event(KeyEvent::from(KeyCode::Char('Y')));
+    assert_eq!(app.pending_yank.as_deref(), Some("web app"));
+
+    app.input_mode = InputMode::Normal;
+    app.do_action(KeyCode::Char('n'));
+    app.handle_key_event(KeyEvent::from(KeyCode::Char('y')));
+    assert_eq!(app.pending_yank.as_deref(), Some("zellij -l compact"));
+}
+
+#[test]
+fn test_session_ages() {
+    let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+    let api = Session {
+        created: Some(now - Duration::from_secs(273600)),
+        ..session("api", false)
+    };
+    assert_eq!(api.age_secs(now), Some(273600));
+    assert_eq!(helpers::format_age(273600), "3d 4h");
+    // The age keeps growing between refreshes
+    assert_eq!(api.age_secs(now + Duration::from_secs(60)), Some(273660));
+    // A clock that went backwards doesn't make ages negative
+    assert_eq!(api.age_secs(now - Duration::from_secs(273605)), Some(0));
+
+    let unknown = Session {
+        created: None,
+        ..api
+    };
+    assert_eq!(unknown.age_secs(now), None);
+
+    let t = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let zone = |secs: i32| chrono::FixedOffset::east_opt(secs).unwrap();
+    assert_eq!(helpers::format_timestamp(t, &zone(0)), "2023-11-14 22:13");
+    assert_eq!(
+        helpers::format_timestamp(t, &zone(2 * 3600)),
+        "2023-11-15 00:13"
+    );
+    assert_eq!(
+        helpers::format_timestamp(t, &zone(-5 * 3600 - 1800)),
+        "2023-11-14 16:43"
+    );
+    assert_eq!(
+        helpers::format_timestamp(SystemTime::UNIX_EPOCH, &chrono::Utc),
+        "1970-01-01 00:00"
+    );
+}
+
+#[test]
+fn test_sort_modes() {
+    let time = |secs: u64| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+    let at = |secs: u64| Some(time(secs));
+    let mut app = App {
+        action: ACTIONS[0],
+        sessions: vec![
+            Session {
+                created: at(100),
+                ..session("bravo", true)
+            },
+            Session {
+                created: at(300),
+                ..session("alpha", false)
+            },
+            Session {
+                created: None,
+                ..session("delta", false)
+            },
+            Session {
+                created: at(200),
+                ..session("charlie", false)
+            },
+        ],
+        sort_mode: SortMode::Age,
+        last_attached: HashMap::from([
+            ("bravo".to_string(), time(50)),
+            ("delta".to_string(), time(40)),
+        ]),
+        current_session: Some("charlie".to_string()),
+        selected_session: 0,
+        focused_list: ListType::Session,
+        input_mode: InputMode::Normal,
+        ..App::default()
+    };
+    let names = |app: &App| {
+        app.sessions
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    // "bravo" is highlighted and stays highlighted through every re-sort
+    app.sort_sessions();
+    assert_eq!(names(&app), "alpha charlie bravo delta");
+    assert_eq!(app.selected_session().unwrap().name, "bravo");
+
+    let mut seen = Vec::new();
+    for _ in 0..4 {
+        app.sort_mode = app.sort_mode.next();
+        app.sort_sessions();
+        seen.push(names(&app));
+        assert_eq!(app.selected_session().unwrap().name, "bravo");
+    }
+    assert_eq!(
+        seen,
+        [
+            "alpha bravo charlie delta",
+            "charlie alpha delta bravo",
+            "bravo delta alpha charlie",
+            "alpha charlie bravo delta",
+        ]
+    );
+
+    let tmp = tempfile::tempdir().unwrap();
+    let dir = tmp.path();
+    assert_eq!(state::load_sort_mode(dir), SortMode::Age);
+    state::save_sort_mode(dir, SortMode::Attached).unwrap();
+    assert_eq!(state::load_sort_mode(dir), SortMode::Attached);
+
+    state::record_attach(dir, "api", time(10)).unwrap();
+    let history = state::record_attach(dir, "my db", time(20)).unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(state::load_attach_history(dir), history);
+    assert_eq!(history.get("my db"), Some(&time(20)));
+
+    // Deleted sessions leave the history
+    let history = state::forget_attached(dir, &["api".to_string()]).unwrap();
+    assert_eq!(history.keys().collect::<Vec<_>>(), ["my db"]);
+    assert_eq!(state::load_attach_history(dir), history);
+}
+
+#[test]
+fn test_unified_sessions_view() {
+    assert_eq!(
+        zellij::parse_client_count(
+            "CLIENT_ID ZELLIJ_PANE_ID RUNNING_COMMAND\n1 terminal_1 vim\n2 terminal_3 N/A\n"
+        ),
+        2
+    );
+    assert_eq!(zellij::parse_client_count(""), 0);
+
+    let sessions_view = ACTIONS.into_iter().find(|a| a.title == "Sessions").unwrap();
+    let mut app = App {
+        action: sessions_view,
+        sessions: vec![session("old", true), session("api", false)],
+        session_details: HashMap::from([(
+            "api".to_string(),
+            SessionDetail {
+                tabs: vec![TabInfo::default(), TabInfo::default()],
+                clients: Some(3),
+                ..SessionDetail::default()
+            },
+        )]),
+        current_session: None,
+        selected_session: 0,
+        focused_list: ListType::Session,
+        input_mode: InputMode::Normal,
+        ..App::default()
+    };
+
+    let mut buf = Buffer::empty(Rect::new(0, 0, 120, 20));
+    app.render(buf.area, &mut buf);
+    let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+    assert!(text.contains("Clients"));
+    assert!(text.contains("exited"));
+    assert!(text.contains("live"));
+    // Details still on their way show as loading rather than holding up the UI
+    assert!(text.contains("…"));
+    app.load_all_details();
+    assert!(app.detail_loader.is_busy());
+    assert!(!app.session_details.contains_key("old"));
+
+    // Shortcuts act on the row instead of switching views, when they apply
+    assert_eq!(
+        app.handle_key_event(KeyEvent::from(KeyCode::Char('k'))),
+        None
+    );
+    assert_eq!(
+        app.input_mode,
+        InputMode::Status("old has already exited".to_string())
+    );
+    assert_eq!(app.action, sessions_view);
+
+    app.input_mode = InputMode::Normal;
+    app.selected_session = 1;
+    app.handle_key_event(KeyEvent::from(KeyCode::Char('d')));
+    assert_eq!(
+        app.input_mode,
+        InputMode::Status("api is still running; kill it before deleting".to_string())
+    );
+
+    app.input_mode = InputMode::Normal;
+    assert_eq!(
+        app.handle_key_event(KeyEvent::from(KeyCode::Char('a'))),
+        Some(vec!["attach".to_string(), "api".to_string()])
+    );
+    app.exit = false;
+    assert_eq!(
+        app.handle_key_event(KeyEvent::from(KeyCode::Enter)),
+        Some(vec!["attach".to_string(), "api".to_string()])
+    );
+}
+
+#[test]
+fn test_session_columns() {
+    use columns::{ColumnKind, parse_columns, replace_columns_block};
+
+    assert_eq!(helpers::truncate_to_width("short", 10), "short");
+    assert_eq!(helpers::truncate_to_width("backend-api", 8), "backend…");
+    // Wide characters take two cells each
+    assert_eq!(
+        helpers::truncate_to_width("日本語のセッション", 7),
+        "日本語…"
+    );
+    assert_eq!(helpers::truncate_to_width("日本語", 0), "");
+    assert_eq!(helpers::format_kb(512), "512K");
+    assert_eq!(helpers::format_kb(12_595), "12.3M");
+    assert_eq!(
+        zellij::parse_vm_rss("Name:\tzellij\nVmRSS:\t   20480 kB\n"),
+        Some(20480)
+    );
+
+    assert_eq!(parse_columns("theme \"dark\""), None);
+    let src = "// zellui\ntheme \"dark\"\nsession_columns {\n    memory\n    name width=24\n    bogus\n}\nother 1\n";
+    let parsed = parse_columns(src).unwrap();
+    let shown: Vec<(ColumnKind, Option<u16>)> = parsed
+        .iter()
+        .filter(|c| c.shown)
+        .map(|c| (c.kind, c.width))
+        .collect();
+    assert_eq!(
+        shown,
+        [(ColumnKind::Memory, None), (ColumnKind::Name, Some(24))]
+    );
+    assert_eq!(parsed.len(), ColumnKind::ALL.len());
+
+    // The picker reorders, hides and resizes, and saving only touches the block
+    let mut app = App {
+        action: ACTIONS.into_iter().find(|a| a.title == "Sessions").unwrap(),
+        columns: parsed,
+        focused_list: ListType::Session,
+        input_mode: InputMode::Normal,
+        ..App::default()
+    };
+    for code in [
+        KeyCode::Char('v'),
+        KeyCode::Down,
+        KeyCode::Char('<'),
+        KeyCode::Char('-'),
+        KeyCode::Down,
+        KeyCode::Char(' '),
+        KeyCode::Esc,
+    ] {
+        app.handle_key_event(KeyEvent::from(code));
+    }
+    assert_eq!(app.input_mode, InputMode::Normal);
+    let rewritten = replace_columns_block(src, &columns::columns_block(&app.columns));
+    assert_eq!(
+        rewritten,
+        "// zellui\ntheme \"dark\"\nsession_columns {\n    name width=23\n}\nother 1\n"
+    );
+    assert_eq!(
+        replace_columns_block("theme \"dark\"", "session_columns {\n}\n"),
+        "theme \"dark\"\nsession_columns {\n}\n"
+    );
+
+    // Widths from the config are held to what the picker allows
+    let wide = parse_columns(
+        "session_columns {\n    name width=40000\n    status width=0\n    age width=40000\n}\n",
+    )
+    .unwrap();
+    let widths: Vec<Option<u16>> = wide.iter().filter(|c| c.shown).map(|c| c.width).collect();
+    assert_eq!(widths, [Some(200), Some(1), Some(200)]);
+    app.columns = wide;
+    app.sessions = vec![session("api", false)];
+    let area = Rect::new(0, 0, 100, 10);
+    let mut buf = Buffer::empty(area);
+    (&app).render(area, &mut buf);
+}
+
+#[test]
+fn test_session_groups() {
+    use groups::{GroupBy, parse_group_by};
+
+    let dash = GroupBy::default();
+    assert_eq!(dash.key("api-main"), Some("api".to_string()));
+    assert_eq!(dash.key("api"), None);
+    assert_eq!(dash.key("-odd"), None);
+    let by_capture = parse_group_by(r#"session_groups regex=r"^\w+/(\w+)""#)
+        .unwrap()
+        .unwrap();
+    assert_eq!(by_capture.key("acme/web-fix"), Some("web".to_string()));
+    assert_eq!(by_capture.key("scratch"), None);
+    let whole_match = parse_group_by(r#"session_groups regex="^[a-z]+""#)
+        .unwrap()
+        .unwrap();
+    assert_eq!(whole_match.key("db2-x"), Some("db".to_string()));
+    assert!(matches!(
+        parse_group_by("session_groups delimiter=\"/\""),
+        Ok(Some(GroupBy::Delimiter(d))) if d == "/"
+    ));
+    assert!(parse_group_by("session_groups regex=\"(\"").is_err());
+    assert!(parse_group_by("session_groups").is_err());
+    assert!(matches!(parse_group_by("theme \"x\""), Ok(None)));
+
+    let mut app = App {
+        action: ACTIONS[0],
+        sessions: vec![
+            session("api-main", false),
+            session("scratch", false),
+            session("web-fix", true),
+            session("api-old", true),
+        ],
+        grouped: true,
+        group_by: GroupBy::default(),
+        current_session: None,
+        selected_session: 0,
+        focused_list: ListType::Session,
+        input_mode: InputMode::Normal,
+        ..App::default()
+    };
+    let labels = |app: &App| {
+        app.session_rows()
+            .iter()
+            .map(|row| match row {
+                SessionRow::Group { name, sessions, .. } => {
+                    format!("[{}:{}]", name, sessions.len())
+                }
+                SessionRow::Session(s) => s.name.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    assert_eq!(
+        labels(&app),
+        "[api:2] api-main api-old scratch [web:1] web-fix"
+    );
+    assert_eq!(app.selected_session(), None);
+
+    // Enter on a header collapses it in the Attach view; Space expands it
+    assert_eq!(app.handle_key_event(KeyEvent::from(KeyCode::Enter)), None);
+    assert_eq!(labels(&app), "[api:2] scratch [web:1] web-fix");
+    app.handle_key_event(KeyEvent::from(KeyCode::Char(' ')));
+    assert_eq!(
+        labels(&app),
+        "[api:2] api-main api-old scratch [web:1] web-fix"
+    );
+
+    // Space on a member collapses its group and lands on the header
+    app.selected_session = 2;
+    app.handle_key_event(KeyEvent::from(KeyCode::Char(' ')));
+    assert_eq!(app.selected_session, 0);
+    assert_eq!(labels(&app), "[api:2] scratch [web:1] web-fix");
+
+    // Ungrouping keeps the highlighted session
+    app.collapsed_groups.clear();
+    app.selected_session = 3;
+    app.handle_key_event(KeyEvent::from(KeyCode::Char('z')));
+    assert!(!app.grouped);
+    assert_eq!(app.selected_session().unwrap().name, "scratch");
+    app.handle_key_event(KeyEvent::from(KeyCode::Char('z')));
+
+    // Group kill and delete ask first and only touch the matching sessions
+    app.action = ACTIONS[2]; // Delete: exited sessions only
+    app.selected_session = 2;
+    assert_eq!(labels(&app), "[web:1] web-fix [api:1] api-old");
+    app.handle_key_event(KeyEvent::from(KeyCode::Enter));
+    assert_eq!(app.input_mode, InputMode::Confirm(Confirm::DeleteGroup));
+    assert_eq!(
+        app.prompt_line().unwrap(),
+        "Delete 1 session(s) in api: api-old? (y/n)"
+    );
+    app.handle_key_event(KeyEvent::from(KeyCode::Char('n')));
+    assert_eq!(app.input_mode, InputMode::Status("Cancelled".to_string()));
+
+    app.action = ACTIONS.into_iter().find(|a| a.title == "Sessions").unwrap();
+    app.input_mode = InputMode::Normal;
+    app.selected_session = 4; // [web]
+    app.handle_key_event(KeyEvent::from(KeyCode::Char('k')));
+    assert_eq!(
+        app.input_mode,
+        InputMode::Status("No live sessions in web".to_string())
+    );
+}
+
+#[test]
+fn test_pins() {
+    let mut app = App {
+        action: ACTIONS[0],
+        sessions: vec![
+            session("alpha", false),
+            session("bravo", false),
+            session("charlie", false),
+        ],
+        layouts: vec![
+            KdlFile::from_path("dev.kdl".to_string(), PathBuf::from("/tmp/layouts/dev.kdl")),
+            KdlFile::builtin("compact", "Single compact status bar"),
+            KdlFile::builtin("strider", "File tree on the left"),
+        ],
+        sort_mode: SortMode::Name,
+        pins: Pins {
+            sessions: HashSet::from(["charlie".to_string(), "bravo".to_string()]),
+            layouts: HashSet::from(["strider".to_string()]),
+        },
+        selected_session: 0,
+        focused_list: ListType::Session,
+        input_mode: InputMode::Normal,
+        ..App::default()
+    };
+    let names = |app: &App| {
+        app.sessions
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    // Pinned sessions come first, in sort mode order, and keep the selection
+    app.sort_sessions();
+    assert_eq!(names(&app), "bravo charlie alpha");
+    assert_eq!(app.selected_session().unwrap().name, "alpha");
+    app.sort_layouts();
+    assert_eq!(app.layouts[0].name, "strider");
+    assert_eq!(app.layouts[1].name, "dev.kdl");
+
+    let area = Rect::new(0, 0, 80, 20);
+    let mut buf = Buffer::empty(area);
+    (&app).render(area, &mut buf);
+    let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+    assert!(text.contains("1. ★ bravo"));
+    assert!(text.contains("3. alpha"));
+
+    let tmp = tempfile::tempdir().unwrap();
+    let dir = tmp.path();
+    assert_eq!(state::load_pins(dir), Pins::default());
+    state::save_pins(dir, &app.pins).unwrap();
+    assert_eq!(state::load_pins(dir), app.pins);
+
+    // Deleting a session unpins it
+    app.data_dir = Some(dir.to_path_buf());
+    app.forget_sessions(&["bravo".to_string()]);
+    assert_eq!(app.pins.sessions, HashSet::from(["charlie".to_string()]));
+    assert_eq!(state::load_pins(dir), app.pins);
+}
+
+#[test]
+fn test_session_meta() {
+    assert_eq!(
+        state::parse_tags("#infra, demo  #infra"),
+        vec!["infra".to_string(), "demo".to_string()]
+    );
+
+    let mut app = App {
+        action: ACTIONS[0],
+        sessions: vec![
+            session("api", false),
+            session("db", false),
+            session("web", false),
+        ],
+        session_meta: HashMap::from([
+            (
+                "db".to_string(),
+                SessionMeta {
+                    tags: vec!["infra".to_string()],
+                    note: "client demo\tdon't kill".to_string(),
+                },
+            ),
+            (
+                "web".to_string(),
+                SessionMeta {
+                    tags: vec!["infra".to_string(), "demo".to_string()],
+                    note: String::new(),
+                },
+            ),
+        ]),
+        selected_session: 2,
+        focused_list: ListType::Session,
+        input_mode: InputMode::Normal,
+        ..App::default()
+    };
+    let press = |app: &mut App, code| app.handle_key_event(KeyEvent::from(code));
+    let type_text = |app: &mut App, text: &str| {
+        for c in text.chars() {
+            press(app, KeyCode::Char(c));
+        }
+    };
+
+    // Filtering keeps the highlighted session when it still matches
+    press(&mut app, KeyCode::Char('f'));
+    assert_eq!(app.input_mode, InputMode::Input(Prompt::TagFilter));
+    type_text(&mut app, "#infra");
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.tag_filter, ["infra"]);
+    let names: Vec<&str> = app
+        .get_visible_sessions()
+        .iter()
+        .map(|s| s.name.as_str())
+        .collect();
+    assert_eq!(names, ["db", "web"]);
+    assert_eq!(app.selected_session().unwrap().name, "web");
+
+    // Editing starts from the current tags
+    press(&mut app, KeyCode::Char('#'));
+    assert_eq!(app.input_mode, InputMode::Input(Prompt::Tags));
+    assert_eq!(app.input_buffer, "#infra #demo");
+    press(&mut app, KeyCode::Esc);
+
+    let area = Rect::new(0, 0, 100, 20);
+    let mut buf = Buffer::empty(area);
+    (&app).render(area, &mut buf);
+    let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+    assert!(text.contains("(by age, #infra)"));
+    assert!(text.contains("#infra #demo"));
+
+    // Several tags must all match
+    press(&mut app, KeyCode::Char('f'));
+    assert_eq!(app.input_buffer, "#infra");
+    type_text(&mut app, " demo");
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.tag_filter, ["infra", "demo"]);
+    assert_eq!(app.get_visible_sessions().len(), 1);
+
+    press(&mut app, KeyCode::Char('f'));
+    app.input_buffer.clear();
+    press(&mut app, KeyCode::Enter);
+    assert!(app.tag_filter.is_empty());
+    assert_eq!(app.get_visible_sessions().len(), 3);
+
+    // Layouts have no tags or notes
+    app.action = ACTIONS[3];
+    press(&mut app, KeyCode::Char('#'));
+    press(&mut app, KeyCode::Char('i'));
+    assert_eq!(app.input_mode, InputMode::Normal);
+    app.action = ACTIONS[0];
+
+    let tmp = tempfile::tempdir().unwrap();
+    let dir = tmp.path();
+    app.session_meta
+        .insert("api".to_string(), SessionMeta::default());
+    state::save_session_meta(dir, &app.session_meta).unwrap();
+    let loaded = state::load_session_meta(dir);
+    assert_eq!(loaded.len(), 2);
+    assert_eq!(loaded["db"].note, "client demo don't kill");
+    assert_eq!(loaded["web"], app.session_meta["web"]);
+
+    // Deleted sessions lose their tags, notes and attach history
+    state::record_attach(dir, "db", SystemTime::now()).unwrap();
+    app.data_dir = Some(dir.to_path_buf());
+    app.forget_sessions(&["db".to_string(), "api".to_string()]);
+    assert!(!app.session_meta.contains_key("db"));
+    assert!(app.session_meta.contains_key("web"));
+    let loaded = state::load_session_meta(dir);
+    assert_eq!(loaded.len(), 1);
+    assert!(loaded.contains_key("web"));
+    assert!(state::load_attach_history(dir).is_empty());
+    assert!(app.last_attached.is_empty());
+}
+
+#[test]
+fn test_detail_loader() {
+    let wait = |loader: &mut zellij::DetailLoader| {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut loaded = Vec::new();
+        while loader.is_busy() && Instant::now() < deadline {
+            loaded.extend(loader.poll());
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        loaded
+    };
+
+    let mut loader = zellij::DetailLoader::default();
+    loader.request(&session("zellui-test-gone", true));
+    loader.request(&session("zellui-test-gone", true));
+    loader.request_memory();
+    loader.request_screen("zellui-test-gone");
+    loader.request_screen("zellui-test-gone");
+    let loaded = wait(&mut loader);
+    assert_eq!(loaded.len(), 3);
+    for loaded in loaded {
+        match loaded {
+            zellij::Loaded::Detail(name, detail) => {
+                assert_eq!(name, "zellui-test-gone");
+                assert!(detail.error.is_some());
+            }
+            // No zellij servers with these names
+            zellij::Loaded::Memory(memory) => assert!(!memory.contains_key("zellui-test-gone")),
+            zellij::Loaded::Screen(name, screen) => {
+                assert_eq!(name, "zellui-test-gone");
+                assert!(screen.is_err());
+            }
+        }
+    }
+
+    // Answers to requests made before a reset are dropped
+    loader.request(&session("zellui-test-stale", true));
+    loader.reset();
+    assert!(!loader.is_busy());
+    std::thread::sleep(Duration::from_millis(50));
+    assert!(loader.poll().is_empty());
+}
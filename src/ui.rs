@@ -31,6 +31,10 @@ impl Widget for &App {
                 "<G>".blue().bold(),
                 " Execute ".into(),
                 "<Enter>".blue().bold(),
+                " Edit ".into(),
+                "<E>".blue().bold(),
+                " Yank ".into(),
+                "<Y>".blue().bold(),
                 " Refresh ".into(),
                 "<R>".blue().bold(),
                 " Quit ".into(),
@@ -85,6 +89,10 @@ impl Widget for &App {
             InputMode::Status(ref msg) => {
                 ratatui::text::Text::from(vec![Line::from(vec![msg.clone().green()])]).bold()
             }
+            InputMode::Input(_) | InputMode::Confirm(_) => ratatui::text::Text::from(vec![
+                Line::from(vec![self.prompt_line().unwrap_or_default().yellow()]),
+            ])
+            .bold(),
             _ => ratatui::text::Text::from(vec![]),
         };
 
@@ -0,0 +1,462 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::helpers::get_zellij_cache_dir;
+use crate::{Session, SessionDetail, TabInfo, kdl};
+
+/// Hands the terminal over to zellij for good. On Unix zellui replaces itself
+/// with zellij via `exec`, so this only returns if that fails; otherwise, and
+/// as the fallback, zellij is spawned and its exit status returned.
+pub fn hand_over(args: &[String]) -> io::Result<ExitStatus> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        // `exec` only returns on error, in which case try a plain spawn
+        let _ = Command::new("zellij").args(args).exec();
+    }
+    Command::new("zellij").args(args).status()
+}
+
+/// zellij arguments for starting a session, optionally named, with a layout
+/// and config.
+pub fn new_session_args(
+    name: Option<&str>,
+    layout: Option<&str>,
+    config: Option<&str>,
+) -> Vec<String> {
+    let mut args = Vec::new();
+    for (flag, value) in [("-s", name), ("-l", layout), ("-c", config)] {
+        if let Some(value) = value {
+            args.push(flag.to_string());
+            args.push(value.to_string());
+        }
+    }
+    args
+}
+
+/// Starts a detached session to switch to from inside zellij.
+pub fn create_background_session(
+    name: &str,
+    layout: Option<&str>,
+    config: Option<&str>,
+) -> io::Result<()> {
+    let mut command = Command::new("zellij");
+    if let Some(config) = config {
+        command.args(["--config", config]);
+    }
+    command.args(["attach", "--create-background", name]);
+    if let Some(layout) = layout {
+        command.args(["options", "--default-layout", layout]);
+    }
+    let output = command.output()?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ))
+    }
+}
+
+/// Runs `zellij --session <session> action <args>` and returns its stdout,
+/// turning a non-zero exit into an error carrying zellij's stderr.
+fn action_output(session: &str, args: &[&str]) -> io::Result<String> {
+    let output = Command::new("zellij")
+        .args(["--session", session, "action"])
+        .args(args)
+        .output()?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ))
+    }
+}
+
+pub fn query_tab_names(session: &str) -> io::Result<Vec<String>> {
+    Ok(action_output(session, &["query-tab-names"])?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Counts the rows of `zellij action list-clients` output, below its header.
+pub fn parse_client_count(output: &str) -> usize {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("CLIENT_ID"))
+        .count()
+}
+
+pub fn client_count(session: &str) -> io::Result<usize> {
+    action_output(session, &["list-clients"]).map(|output| parse_client_count(&output))
+}
+
+/// Resident memory of every zellij server, by session name, found in one
+/// pass over `/proc` by their `--server <socket dir>/<session>` arguments.
+/// Empty where there is no `/proc`.
+pub fn server_memory() -> HashMap<String, u64> {
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return HashMap::new();
+    };
+    entries
+        .filter_map(|entry| {
+            let dir = entry.ok()?.path();
+            let cmdline = fs::read(dir.join("cmdline")).ok()?;
+            let args: Vec<&[u8]> = cmdline.split(|&b| b == 0).collect();
+            let socket = args
+                .iter()
+                .position(|&arg| arg == b"--server")
+                .and_then(|i| args.get(i + 1))?;
+            let session = Path::new(std::str::from_utf8(socket).ok()?)
+                .file_name()?
+                .to_string_lossy()
+                .to_string();
+            let rss = parse_vm_rss(&fs::read_to_string(dir.join("status")).ok()?)?;
+            Some((session, rss))
+        })
+        .collect()
+}
+
+/// Reads `VmRSS` (in KiB) out of a `/proc/<pid>/status` file.
+pub fn parse_vm_rss(status: &str) -> Option<u64> {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
+
+pub fn dump_layout(session: &str) -> io::Result<String> {
+    action_output(session, &["dump-layout"])
+}
+
+/// Dumps what's on screen in the session's focused pane, via a file in a
+/// private temp directory that is removed afterwards.
+pub fn dump_screen(session: &str) -> io::Result<String> {
+    let dir = tempfile::Builder::new()
+        .prefix("zellui-screen-")
+        .tempdir()?;
+    let path = dir.path().join("screen.txt");
+    let path_arg = path.to_string_lossy().to_string();
+    action_output(session, &["dump-screen", &path_arg])?;
+    fs::read_to_string(&path)
+}
+
+/// Hidden flag zellui runs a copy of itself with to focus a tab once an
+/// attach has landed: `--focus-tab-when-attached <session> <tab> <clients>`.
+pub const FOCUS_TAB_FLAG: &str = "--focus-tab-when-attached";
+/// How long the helper waits for the attach before giving up.
+const ATTACH_TIMEOUT: Duration = Duration::from_secs(30);
+const ATTACH_POLL: Duration = Duration::from_millis(200);
+
+/// Starts a detached copy of zellui that moves the client attaching to
+/// `session` next onto `tab`. `clients` is how many are connected now.
+pub fn spawn_tab_focus(session: &str, tab: &str, clients: usize) -> io::Result<()> {
+    Command::new(std::env::current_exe()?)
+        .args([FOCUS_TAB_FLAG, session, tab, &clients.to_string()])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+}
+
+/// Waits for `session` to have more than `clients` clients, i.e. for the
+/// attach or switch to land, then focuses `tab`. Does nothing if no client
+/// shows up within `ATTACH_TIMEOUT`, e.g. because the attach failed.
+pub fn focus_tab_when_attached(session: &str, tab: &str, clients: usize) -> io::Result<()> {
+    let deadline = Instant::now() + ATTACH_TIMEOUT;
+    while Instant::now() < deadline {
+        if client_count(session).is_ok_and(|n| n > clients) {
+            return action_output(session, &["go-to-tab-name", tab]).map(|_| ());
+        }
+        thread::sleep(ATTACH_POLL);
+    }
+    Ok(())
+}
+
+/// Tabs and panes of a running session. Falls back to bare tab names when
+/// the layout can't be dumped.
+pub fn live_session_detail(session: &str) -> SessionDetail {
+    let clients = client_count(session).ok();
+    match dump_layout(session) {
+        Ok(layout) => SessionDetail {
+            tabs: kdl::layout_tabs(&layout),
+            clients,
+            error: None,
+        },
+        Err(dump_err) => match query_tab_names(session) {
+            Ok(names) => SessionDetail {
+                tabs: names
+                    .into_iter()
+                    .map(|name| TabInfo {
+                        name,
+                        ..TabInfo::default()
+                    })
+                    .collect(),
+                clients,
+                error: None,
+            },
+            Err(_) => SessionDetail {
+                tabs: Vec::new(),
+                clients,
+                error: Some(dump_err.to_string()),
+            },
+        },
+    }
+}
+
+/// Finds the serialized layout zellij keeps for resurrecting `session`.
+///
+/// The cache is split per zellij version (`<cache>/<version>/session_info/
+/// <session>/session-layout.kdl`), so every version directory is searched and
+/// the most recently written file wins.
+pub fn resurrection_layout_path(cache_dir: &Path, session: &str) -> Option<PathBuf> {
+    fs::read_dir(cache_dir)
+        .ok()?
+        .filter_map(|entry| {
+            let path = entry
+                .ok()?
+                .path()
+                .join("session_info")
+                .join(session)
+                .join("session-layout.kdl");
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            Some((modified, path))
+        })
+        .max()
+        .map(|(_, path)| path)
+}
+
+/// Reads the layout zellij serialized for an exited session.
+pub fn resurrection_layout(session: &str) -> io::Result<String> {
+    let path = get_zellij_cache_dir()
+        .and_then(|dir| resurrection_layout_path(&dir, session))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No resurrection data found"))?;
+    fs::read_to_string(path)
+}
+
+/// The layout of any session: dumped from zellij when it's live, read from
+/// the resurrection cache when it has exited.
+pub fn session_layout(session: &Session) -> io::Result<String> {
+    if session.exited {
+        resurrection_layout(&session.name)
+    } else {
+        dump_layout(&session.name)
+    }
+}
+
+/// Tabs and panes of an exited session, read from the resurrection cache.
+pub fn exited_session_detail(session: &str) -> SessionDetail {
+    match resurrection_layout(session) {
+        Ok(layout) => SessionDetail {
+            tabs: kdl::layout_tabs(&layout),
+            clients: None,
+            error: None,
+        },
+        Err(error) => SessionDetail {
+            tabs: Vec::new(),
+            clients: None,
+            error: Some(error.to_string()),
+        },
+    }
+}
+
+/// Work for the loader thread, tagged with the loader generation it was
+/// asked for in.
+#[derive(Debug)]
+enum Request {
+    Detail {
+        generation: u64,
+        name: String,
+        exited: bool,
+    },
+    Memory {
+        generation: u64,
+    },
+    Screen {
+        generation: u64,
+        name: String,
+    },
+}
+
+impl Request {
+    fn generation(&self) -> u64 {
+        match *self {
+            Request::Detail { generation, .. }
+            | Request::Memory { generation }
+            | Request::Screen { generation, .. } => generation,
+        }
+    }
+}
+
+/// What the loader thread has fetched.
+#[derive(Debug)]
+pub enum Loaded {
+    Detail(String, SessionDetail),
+    /// Server memory of every session, from `server_memory`.
+    Memory(HashMap<String, u64>),
+    /// A session's screen, from `dump_screen`.
+    Screen(String, io::Result<String>),
+}
+
+/// Fetches session details and screens on a background thread, so the
+/// zellij calls and cache reads behind them never hold up the UI, not even
+/// for a hung session. The most recent request is served first, so the
+/// highlighted session doesn't wait behind a backlog.
+#[derive(Debug)]
+pub struct DetailLoader {
+    requests: Sender<Request>,
+    results: Receiver<(u64, Loaded)>,
+    /// Bumped by `reset`; answers to older requests are dropped.
+    generation: Arc<AtomicU64>,
+    pending: HashSet<String>,
+    memory_pending: bool,
+    /// The session whose screen is on its way.
+    screen_pending: Option<String>,
+}
+
+impl Default for DetailLoader {
+    fn default() -> Self {
+        let (requests, inbox) = mpsc::channel::<Request>();
+        let (outbox, results) = mpsc::channel();
+        let generation = Arc::new(AtomicU64::new(0));
+        let current = Arc::clone(&generation);
+        // Ends once the loader, and with it the request sender, is dropped
+        thread::spawn(move || {
+            let mut queue = Vec::new();
+            loop {
+                if queue.is_empty() {
+                    match inbox.recv() {
+                        Ok(request) => queue.push(request),
+                        Err(_) => return,
+                    }
+                }
+                queue.extend(inbox.try_iter());
+                let Some(request) = queue.pop() else {
+                    continue;
+                };
+                let generation = request.generation();
+                if generation != current.load(Ordering::Relaxed) {
+                    continue;
+                }
+                let loaded = match request {
+                    Request::Detail { name, exited, .. } => {
+                        let detail = if exited {
+                            exited_session_detail(&name)
+                        } else {
+                            live_session_detail(&name)
+                        };
+                        Loaded::Detail(name, detail)
+                    }
+                    Request::Memory { .. } => Loaded::Memory(server_memory()),
+                    Request::Screen { name, .. } => {
+                        let screen = dump_screen(&name);
+                        Loaded::Screen(name, screen)
+                    }
+                };
+                if outbox.send((generation, loaded)).is_err() {
+                    return;
+                }
+            }
+        });
+        Self {
+            requests,
+            results,
+            generation,
+            pending: HashSet::new(),
+            memory_pending: false,
+            screen_pending: None,
+        }
+    }
+}
+
+impl DetailLoader {
+    /// Queues `session` unless it is already on its way.
+    pub fn request(&mut self, session: &Session) {
+        if self.pending.insert(session.name.clone()) {
+            let _ = self.requests.send(Request::Detail {
+                generation: self.generation.load(Ordering::Relaxed),
+                name: session.name.clone(),
+                exited: session.exited,
+            });
+        }
+    }
+
+    /// Queues a `server_memory` scan unless one is already on its way.
+    pub fn request_memory(&mut self) {
+        if !self.memory_pending {
+            self.memory_pending = true;
+            let _ = self.requests.send(Request::Memory {
+                generation: self.generation.load(Ordering::Relaxed),
+            });
+        }
+    }
+
+    /// Queues a `dump_screen` of `name` unless it is already on its way.
+    pub fn request_screen(&mut self, name: &str) {
+        if self.screen_pending.as_deref() != Some(name) {
+            self.screen_pending = Some(name.to_string());
+            let _ = self.requests.send(Request::Screen {
+                generation: self.generation.load(Ordering::Relaxed),
+                name: name.to_string(),
+            });
+        }
+    }
+
+    /// The session a screen has been asked for and not yet arrived.
+    pub fn screen_pending(&self) -> Option<&str> {
+        self.screen_pending.as_deref()
+    }
+
+    pub fn is_busy(&self) -> bool {
+        !self.pending.is_empty() || self.memory_pending || self.screen_pending.is_some()
+    }
+
+    /// Forgets everything requested so far, e.g. on refresh.
+    pub fn reset(&mut self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+        self.pending.clear();
+        self.memory_pending = false;
+        self.screen_pending = None;
+    }
+
+    /// What has arrived since the last call.
+    pub fn poll(&mut self) -> Vec<Loaded> {
+        let generation = self.generation.load(Ordering::Relaxed);
+        let mut arrived = Vec::new();
+        for (asked_in, loaded) in self.results.try_iter() {
+            if asked_in != generation {
+                continue;
+            }
+            match loaded {
+                Loaded::Detail(ref name, _) => {
+                    self.pending.remove(name);
+                }
+                Loaded::Memory(_) => self.memory_pending = false,
+                Loaded::Screen(ref name, _) => {
+                    if self.screen_pending.as_ref() == Some(name) {
+                        self.screen_pending = None;
+                    }
+                }
+            }
+            arrived.push(loaded);
+        }
+        arrived
+    }
+}